@@ -1,2 +1,2 @@
 cargo build -rq
-./target/release/aoc25 -b a
+./target/release/aoc25 -b a "$@"
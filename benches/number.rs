@@ -0,0 +1,59 @@
+//! Compares [`number::parse_int`] with the unchecked parser the days used
+//! before it, on puzzle-like numbers, and fails if it is any slower:
+//! `cargo bench --bench number`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+// The crate is a binary, so the parser is compiled in directly, together
+// with the errors it reports.
+#[allow(unused)]
+#[path = "../src/error/mod.rs"]
+mod error;
+#[allow(unused)]
+#[path = "../src/util/number.rs"]
+mod number;
+
+/// The parser the days used before [`number::parse_int`].
+fn unchecked_parse(slice: &[u8]) -> i64 {
+    slice
+        .iter()
+        .map(|&c| (c - b'0') as i64)
+        .reduce(|acc, next| acc * 10 + next)
+        .unwrap()
+}
+
+/// How long `parse` takes for all `numbers`.
+fn time(numbers: &[&[u8]], parse: impl Fn(&[u8]) -> i64) -> Duration {
+    let start = Instant::now();
+    let sum = numbers
+        .iter()
+        .map(|&n| parse(black_box(n)))
+        .fold(0i64, i64::wrapping_add);
+    black_box(sum);
+    start.elapsed()
+}
+
+fn main() {
+    // One input of puzzle-like numbers, like the days parse them from.
+    let input = (0..200_000u64)
+        .map(|i| (i * 2_654_435_761 % 10u64.pow(1 + (i % 15) as u32)).to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let numbers: Vec<&[u8]> = input.as_bytes().split(|&c| c == b'\n').collect();
+
+    // Alternating between the two lets both see the same load, and the
+    // fastest of many rounds leaves out interruptions.
+    let (mut baseline, mut checked) = (Duration::MAX, Duration::MAX);
+    for _ in 0..200 {
+        baseline = baseline.min(time(&numbers, unchecked_parse));
+        checked = checked.min(time(&numbers, |n| number::parse_int::<i64>(n).unwrap()));
+    }
+    println!("unchecked: {:?}, parse_int: {:?}", baseline, checked);
+    assert!(
+        checked <= baseline,
+        "parse_int is slower than the unchecked parser"
+    );
+}
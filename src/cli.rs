@@ -0,0 +1,499 @@
+use std::time::Duration;
+
+use crate::{
+    day::{Day, Params, Part},
+    error::UsageError,
+    report::Format,
+    util::{benchmark::BenchmarkBudget, benchmark_history::Baseline, input::PuzzleInputType},
+};
+
+pub static HELP: &str = "\
+Usage: aoc25 [<target>...] [<option>...]
+       aoc25 submit <day>.<part> [<option>...]
+       aoc25 cache list|purge [<day>]|verify [<option>...]
+
+Targets:
+  3              Day 3, both parts, example and actual input
+  3.1            Day 3, part 1 only
+  3e, 3a         Day 3, example or actual input only
+  3.1e           Suffixes combine: day 3, part 1, example input
+  1-5            Days 1 through 5; takes the same suffixes (1-5.2a)
+  all            All days; takes the same suffixes (all.1e)
+  e, a           All days, example or actual input only
+  !7             Excludes day 7 from the selection; takes the same suffixes (!7.2a)
+
+  Without targets the latest day is run, or all days if only exclusions are given.
+
+Options:
+  -b, --benchmark            Repeat each part and report timing statistics
+      --warmup <ms>          Time spent warming up before sampling (default 200)
+      --bench-time <ms>      Time budget for sampling a part (default 2000)
+      --bench-runs <n>       Maximum number of samples of a part (default 1000)
+      --compare[=<rev>]      Compare against the latest benchmark, or that of a commit
+      --max-regression <%>   Slowdown that fails a comparison (default 10)
+      --input <path>         Run the targets on this file instead, or on stdin for -
+      --timeout <ms>         Give up on a part after ms milliseconds
+  -j, --jobs <n>             Run up to n targets at once (default 1), not with --benchmark
+      --format <format>      Output as table (default), json or csv
+      --param <name=value>   Override a puzzle parameter
+      --cache-dir <path>     Input cache (default $AOC_CACHE_DIR or $XDG_CACHE_HOME/aoc25)
+      --base-url <url>       Advent of Code host (default $AOC_BASE_URL or adventofcode.com)
+  -h, --help                 Print this help
+
+Options taking a value accept both --option value and --option=value.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunTarget {
+    pub day: u8,
+    pub part: Part,
+    pub input_type: PuzzleInputType,
+}
+
+pub enum Command {
+    Help,
+    Run(Vec<RunTarget>),
+    Submit { day: u8, part: Part },
+    Cache(CacheCommand),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CacheCommand {
+    List,
+    /// Removes the cached inputs of a day, or of all days.
+    Purge(Option<u8>),
+    Verify,
+}
+
+pub struct Options {
+    pub benchmark: bool,
+    pub base_url: Option<String>,
+    pub cache_dir: Option<String>,
+    /// Parameter overrides, each declared by at least one day.
+    pub params: Params,
+    pub budget: BenchmarkBudget,
+    /// Baseline to compare benchmarks against; commits are not yet resolved.
+    pub compare: Option<Baseline>,
+    pub max_regression: f64,
+    pub format: Format,
+    /// Number of targets run at once.
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    /// Path of the input to run on instead of the example and actual ones,
+    /// `-` for stdin.
+    pub input: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            benchmark: false,
+            base_url: None,
+            cache_dir: None,
+            params: Params::new(),
+            budget: BenchmarkBudget::default(),
+            compare: None,
+            max_regression: 10.0,
+            format: Format::Table,
+            jobs: 1,
+            timeout: None,
+            input: None,
+        }
+    }
+}
+
+pub struct Cli {
+    pub command: Command,
+    pub options: Options,
+}
+
+/// Parses the command line (without the program name) against the
+/// implemented `days`.
+pub fn parse(
+    args: impl IntoIterator<Item = String>,
+    days: &[Box<dyn Day>],
+) -> Result<Cli, UsageError> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut is_help = false;
+    // The last given option that only applies to benchmarks.
+    let mut benchmark_option = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') || arg == "-" {
+            positional.push(arg);
+            continue;
+        }
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_owned())),
+            None => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| UsageError(format!("{} needs a value", name)))
+        };
+        match name {
+            "-h" | "--help" => is_help = true,
+            "-b" | "--benchmark" => options.benchmark = true,
+            "--base-url" => options.base_url = Some(value()?),
+            "--cache-dir" => options.cache_dir = Some(value()?),
+            "--param" => {
+                let param = value()?;
+                let (param_name, param_value) = param.split_once('=').ok_or_else(|| {
+                    UsageError(format!(
+                        "Invalid parameter {:?}, expected name=value",
+                        param
+                    ))
+                })?;
+                if !days
+                    .iter()
+                    .flat_map(|d| d.param_specs())
+                    .any(|spec| spec.name == param_name)
+                {
+                    return Err(UsageError(format!("Unknown parameter {}", param_name)));
+                }
+                options.params.set(param_name, param_value);
+            }
+            "--warmup" => {
+                benchmark_option = Some("--warmup");
+                options.budget.warmup = Duration::from_millis(number(name, &value()?)?)
+            }
+            "--bench-time" => {
+                benchmark_option = Some("--bench-time");
+                options.budget.time = Duration::from_millis(number(name, &value()?)?)
+            }
+            "--bench-runs" => {
+                benchmark_option = Some("--bench-runs");
+                options.budget.runs = number(name, &value()?)?
+            }
+            // The revision is optional, so it can only be given inline.
+            "--compare" => {
+                benchmark_option = Some("--compare");
+                options.compare = Some(match inline {
+                    Some(revision) => Baseline::Commit(revision),
+                    None => Baseline::Latest,
+                })
+            }
+            "--max-regression" => {
+                benchmark_option = Some("--max-regression");
+                options.max_regression = number(name, &value()?)?
+            }
+            "--input" => options.input = Some(value()?),
+            "--timeout" => options.timeout = Some(Duration::from_millis(number(name, &value()?)?)),
+            "-j" | "--jobs" => options.jobs = number(name, &value()?)?,
+            "--format" => options.format = value()?.parse().map_err(UsageError)?,
+            _ => return Err(UsageError(format!("Unknown option {}", name))),
+        }
+    }
+
+    if options.jobs == 0 {
+        return Err(UsageError("--jobs must be at least 1".to_owned()));
+    }
+    // Concurrent runs would skew each other's timings.
+    if options.jobs > 1 && options.benchmark {
+        return Err(UsageError(
+            "--jobs cannot be combined with --benchmark".to_owned(),
+        ));
+    }
+    if let Some(option) = benchmark_option
+        && !options.benchmark
+    {
+        return Err(UsageError(format!("{} needs --benchmark", option)));
+    }
+
+    let available: Vec<u8> = days.iter().map(|d| d.number()).collect();
+    let command = if is_help {
+        Command::Help
+    } else if positional.first().is_some_and(|a| a == "submit") {
+        parse_submit(&positional[1..], &available)?
+    } else if positional.first().is_some_and(|a| a == "cache") {
+        parse_cache(&positional[1..], &available)?
+    } else if options.input.is_some() {
+        Command::Run(custom_targets(parse_targets(&positional, &available)?))
+    } else {
+        Command::Run(parse_targets(&positional, &available)?)
+    };
+    Ok(Cli { command, options })
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, UsageError> {
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("Invalid value {:?} for {}", value, flag)))
+}
+
+/// Runs each selected part once, on the custom input.
+fn custom_targets(targets: Vec<RunTarget>) -> Vec<RunTarget> {
+    let mut custom: Vec<RunTarget> = Vec::new();
+    for target in targets {
+        let target = RunTarget {
+            input_type: PuzzleInputType::Custom,
+            ..target
+        };
+        if !custom.contains(&target) {
+            custom.push(target);
+        }
+    }
+    custom
+}
+
+fn parse_cache(args: &[String], available: &[u8]) -> Result<Command, UsageError> {
+    let command = match args {
+        [command] if command == "list" => CacheCommand::List,
+        [command] if command == "verify" => CacheCommand::Verify,
+        [command] if command == "purge" => CacheCommand::Purge(None),
+        [command, day] if command == "purge" => {
+            CacheCommand::Purge(Some(parse_day(day, available)?))
+        }
+        _ => {
+            return Err(UsageError(
+                "Usage: cache list|purge [<day>]|verify".to_owned(),
+            ));
+        }
+    };
+    Ok(Command::Cache(command))
+}
+
+fn parse_submit(args: &[String], available: &[u8]) -> Result<Command, UsageError> {
+    let usage = || UsageError("Usage: submit <day>.<part>".to_owned());
+    let [target] = args else {
+        return Err(usage());
+    };
+    let (day, part) = target.split_once('.').ok_or_else(usage)?;
+    let day = parse_day(day, available)?;
+    let part = parse_part(part)?;
+    Ok(Command::Submit { day, part })
+}
+
+/// Expands the target arguments into run targets in the order given, without
+/// duplicates and without the excluded ones.
+fn parse_targets(args: &[String], available: &[u8]) -> Result<Vec<RunTarget>, UsageError> {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for arg in args {
+        match arg.strip_prefix('!') {
+            Some(spec) => excluded.extend(parse_target(spec, available)?),
+            None => included.extend(parse_target(arg, available)?),
+        }
+    }
+
+    if args.iter().all(|arg| arg.starts_with('!')) {
+        included = match available.iter().max() {
+            _ if !excluded.is_empty() => parse_target("all", available)?,
+            Some(latest) => parse_target(&latest.to_string(), available)?,
+            None => vec![],
+        };
+    }
+
+    let mut targets: Vec<RunTarget> = Vec::new();
+    for target in included {
+        if !excluded.contains(&target) && !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    Ok(targets)
+}
+
+/// Parses a single `<days>[.<part>][e|a]` target, where `<days>` is a day, a
+/// range of days, `all` or empty for all days.
+fn parse_target(spec: &str, available: &[u8]) -> Result<Vec<RunTarget>, UsageError> {
+    if spec.is_empty() {
+        return Err(UsageError("Empty target, see --help".to_owned()));
+    }
+    let (rest, input_types) = match spec.as_bytes()[spec.len() - 1] {
+        b'e' => (&spec[..spec.len() - 1], vec![PuzzleInputType::Example]),
+        b'a' => (&spec[..spec.len() - 1], vec![PuzzleInputType::Actual]),
+        _ => (
+            spec,
+            vec![PuzzleInputType::Example, PuzzleInputType::Actual],
+        ),
+    };
+    let (days, parts) = match rest.split_once('.') {
+        Some((days, part)) => (days, vec![parse_part(part)?]),
+        None => (rest, vec![Part::One, Part::Two]),
+    };
+    let days = match days {
+        "" | "all" => available.to_vec(),
+        _ => match days.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_day(from, available)?, parse_day(to, available)?);
+                if from > to {
+                    return Err(UsageError(format!("Invalid range {}-{}", from, to)));
+                }
+                (from..=to)
+                    .map(|day| parse_day(&day.to_string(), available))
+                    .collect::<Result<_, _>>()?
+            }
+            None => vec![parse_day(days, available)?],
+        },
+    };
+
+    let mut targets = Vec::new();
+    for day in days {
+        for &part in &parts {
+            for &input_type in &input_types {
+                targets.push(RunTarget {
+                    day,
+                    part,
+                    input_type,
+                });
+            }
+        }
+    }
+    Ok(targets)
+}
+
+fn parse_day(day: &str, available: &[u8]) -> Result<u8, UsageError> {
+    let number = day
+        .parse::<u8>()
+        .map_err(|_| UsageError(format!("Invalid day {:?}", day)))?;
+    if !available.contains(&number) {
+        return Err(UsageError(format!("Day {} is not implemented", number)));
+    }
+    Ok(number)
+}
+
+fn parse_part(part: &str) -> Result<Part, UsageError> {
+    match part {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(UsageError(format!(
+            "Invalid part {:?}, expected 1 or 2",
+            part
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn targets(args: &[&str]) -> Result<Vec<String>, UsageError> {
+        let days = crate::day::get_days();
+        let cli = parse(args.iter().map(|a| a.to_string()), &days)?;
+        let Command::Run(targets) = cli.command else {
+            panic!("Expected run targets");
+        };
+        Ok(targets
+            .iter()
+            .map(|t| {
+                let input = match t.input_type {
+                    PuzzleInputType::Example => "e",
+                    PuzzleInputType::Actual => "a",
+                    PuzzleInputType::Custom => "c",
+                };
+                format!("{}.{}{}", t.day, t.part, input)
+            })
+            .collect())
+    }
+
+    #[test]
+    fn targets_with_suffixes() {
+        assert_eq!(targets(&["3"]).unwrap(), ["3.1e", "3.1a", "3.2e", "3.2a"]);
+        assert_eq!(targets(&["3.2"]).unwrap(), ["3.2e", "3.2a"]);
+        assert_eq!(targets(&["3e", "3.1e"]).unwrap(), ["3.1e", "3.2e"]);
+        assert_eq!(targets(&["1-3.2a"]).unwrap(), ["1.2a", "2.2a", "3.2a"]);
+        assert_eq!(targets(&["all.1e"]).unwrap().len(), 12);
+        assert_eq!(targets(&["a"]).unwrap().len(), 24);
+        assert_eq!(targets(&[]).unwrap(), ["12.1e", "12.1a", "12.2e", "12.2a"]);
+    }
+
+    #[test]
+    fn exclusions() {
+        assert_eq!(targets(&["1-3.1a", "!2"]).unwrap(), ["1.1a", "3.1a"]);
+        assert_eq!(targets(&["1.1", "!1a"]).unwrap(), ["1.1e"]);
+        let all_but_seven = targets(&["!7"]).unwrap();
+        assert_eq!(all_but_seven.len(), 44);
+        assert!(!all_but_seven.iter().any(|t| t.starts_with("7.")));
+    }
+
+    #[test]
+    fn custom_input_targets() {
+        assert_eq!(targets(&["9", "--input", "-"]).unwrap(), ["9.1c", "9.2c"]);
+        assert_eq!(targets(&["3.2a", "--input=in.txt"]).unwrap(), ["3.2c"]);
+    }
+
+    #[test]
+    fn invalid_targets() {
+        assert_eq!(targets(&["13"]).unwrap_err().0, "Day 13 is not implemented");
+        assert_eq!(
+            targets(&["10-20"]).unwrap_err().0,
+            "Day 20 is not implemented"
+        );
+        assert!(targets(&["3-1"]).is_err());
+        assert!(targets(&["3.3"]).is_err());
+        assert!(targets(&["3x"]).is_err());
+        assert!(targets(&["!"]).is_err());
+    }
+
+    #[test]
+    fn options() {
+        let days = crate::day::get_days();
+        let args = [
+            "1",
+            "-b",
+            "--format",
+            "csv",
+            "--bench-runs=5",
+            "--param",
+            "connections=5",
+        ];
+        let cli = parse(args.iter().map(|a| a.to_string()), &days).unwrap();
+        assert!(cli.options.benchmark);
+        assert_eq!(cli.options.format, Format::Csv);
+        assert_eq!(cli.options.budget.runs, 5);
+        assert_eq!(cli.options.params.get("connections"), Some("5"));
+
+        let parse = |args: &[&str]| parse(args.iter().map(|a| a.to_string()), &days);
+        assert_eq!(parse(&["-x"]).err().unwrap().0, "Unknown option -x");
+        assert_eq!(
+            parse(&["--format"]).err().unwrap().0,
+            "--format needs a value"
+        );
+        assert!(parse(&["--param", "nope=1"]).is_err());
+        assert!(parse(&["--bench-runs=many"]).is_err());
+        assert_eq!(
+            parse(&["--compare"]).err().unwrap().0,
+            "--compare needs --benchmark"
+        );
+        assert_eq!(
+            parse(&["--max-regression", "5"]).err().unwrap().0,
+            "--max-regression needs --benchmark"
+        );
+        for option in ["--warmup", "--bench-time", "--bench-runs"] {
+            assert_eq!(
+                parse(&[option, "5"]).err().unwrap().0,
+                format!("{} needs --benchmark", option)
+            );
+            assert!(parse(&[option, "5", "-b"]).is_ok());
+        }
+        assert!(parse(&["--compare=HEAD~1", "--max-regression=5", "-b"]).is_ok());
+        assert!(matches!(
+            parse(&["1", "--help"]).unwrap().command,
+            Command::Help
+        ));
+        assert!(matches!(
+            parse(&["submit", "3.1"]).unwrap().command,
+            Command::Submit {
+                day: 3,
+                part: Part::One
+            }
+        ));
+        assert!(parse(&["submit", "3"]).is_err());
+        assert!(matches!(
+            parse(&["cache", "purge", "4"]).unwrap().command,
+            Command::Cache(CacheCommand::Purge(Some(4)))
+        ));
+        assert!(parse(&["cache", "purge", "40"]).is_err());
+        assert!(parse(&["cache"]).is_err());
+        assert_eq!(parse(&["-j", "4"]).unwrap().options.jobs, 4);
+        assert!(parse(&["--jobs=0"]).is_err());
+        assert!(parse(&["--jobs=2", "-b"]).is_err());
+        assert_eq!(
+            parse(&["--timeout", "500"]).unwrap().options.timeout,
+            Some(Duration::from_millis(500))
+        );
+    }
+}
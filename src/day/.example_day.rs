@@ -1,40 +1,32 @@
-use crate::day::Solution;
+use crate::day::{Answer, Example, Part, RunContext, Solution};
 
 use super::Day;
 
 struct DayXX;
 
 impl Solution for DayXX {
+    type Input<'a> = &'a [u8];
+
     fn number(&self) -> u8 {
         XX
     }
 
-    fn get_example(&self) -> Option<&str> {
-        Some(r#"ABC"#)
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(input)
     }
-}
 
-pub fn day() -> Box<dyn Day> {
-    Box::new(DayXX)
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, todo!());
-    }
+examples! {
+    example: r#"ABC"# => {
+        Part::One => 0,
+        Part::Two => 0,
+    },
+}
 
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, todo!());
-    }
+pub fn day() -> Box<dyn Day> {
+    Box::new(DayXX)
 }
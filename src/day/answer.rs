@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+/// The result of a puzzle part.
+///
+/// Integers are stored in their widest form so that answers of different
+/// integer types compare equal; only values beyond `i128` use `UInt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    UInt(u128),
+    Text(String),
+    /// Multi-line answers such as letter art, one entry per row.
+    #[allow(unused)]
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    #[allow(unused)]
+    pub fn grid<I, S>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Answer::Grid(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::UInt(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::UInt(value),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn integers_compare_across_widths() {
+        assert_eq!(Answer::from(42u8), Answer::from(42i64));
+        assert_eq!(Answer::from(42u128), Answer::from(42usize));
+        assert_eq!(Answer::from(u128::MAX), Answer::UInt(u128::MAX));
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn grid_displays_rows() {
+        let answer = Answer::grid(["#..", ".#.", "..#"]);
+        assert_eq!(answer.to_string(), "#..\n.#.\n..#");
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, error::Error, str::FromStr, sync::Arc};
+
+use crate::{day::Part, util::input::PuzzleInputType};
+
+pub trait Logger: Send + Sync {
+    #[allow(unused)]
+    fn log(&self, message: &str);
+}
+
+pub struct StdoutLogger;
+
+impl Logger for StdoutLogger {
+    fn log(&self, message: &str) {
+        println!("{}", message);
+    }
+}
+
+pub struct StderrLogger;
+
+impl Logger for StderrLogger {
+    fn log(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+/// A named parameter a solution reads from its [`RunContext`], with a
+/// default for each input type.
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub parts: &'static [Part],
+    pub example: &'static str,
+    pub actual: &'static str,
+}
+
+impl ParamSpec {
+    pub fn applies_to(&self, part: Part) -> bool {
+        self.parts.contains(&part)
+    }
+
+    fn default_for(&self, input_type: PuzzleInputType) -> &'static str {
+        match input_type {
+            PuzzleInputType::Example => self.example,
+            // Custom inputs are most likely someone else's actual input.
+            PuzzleInputType::Actual | PuzzleInputType::Custom => self.actual,
+        }
+    }
+}
+
+/// Named puzzle parameters, either overrides given on the command line or
+/// the values resolved for a single part.
+#[derive(Debug, Clone, Default)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_owned(), value.to_owned());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Resolves the parameters `specs` declare for `part`, preferring
+    /// overrides in `self` over the declared defaults.
+    pub fn resolve(&self, specs: &[ParamSpec], part: Part, input_type: PuzzleInputType) -> Self {
+        let mut resolved = Params::new();
+        for spec in specs.iter().filter(|s| s.applies_to(part)) {
+            let value = self
+                .get(spec.name)
+                .unwrap_or_else(|| spec.default_for(input_type));
+            resolved.set(spec.name, value);
+        }
+        resolved
+    }
+}
+
+/// Everything a solution may know about the run it is part of.
+#[derive(Clone)]
+pub struct RunContext {
+    pub input_type: PuzzleInputType,
+    pub is_benchmarking: bool,
+    pub params: Params,
+    logger: Arc<dyn Logger>,
+}
+
+impl RunContext {
+    pub fn new(input_type: PuzzleInputType) -> Self {
+        Self {
+            input_type,
+            is_benchmarking: false,
+            params: Params::new(),
+            logger: Arc::new(StdoutLogger),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn example() -> Self {
+        Self::new(PuzzleInputType::Example)
+    }
+
+    pub fn with_logger(mut self, logger: Arc<dyn Logger>) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Reads a parameter declared by the running solution.
+    pub fn param<T: FromStr>(&self, name: &str) -> Result<T, Box<dyn Error>> {
+        let value = self
+            .params
+            .get(name)
+            .ok_or_else(|| format!("Parameter {} is not declared for this part", name))?;
+        value
+            .parse()
+            .map_err(|_| format!("Invalid value {:?} for parameter {}", value, name).into())
+    }
+
+    #[allow(unused)]
+    pub fn log(&self, message: &str) {
+        self.logger.log(message);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn overrides_win_over_defaults() {
+        let specs = [ParamSpec {
+            name: "connections",
+            parts: &[Part::One],
+            example: "10",
+            actual: "1000",
+        }];
+        let mut overrides = Params::new();
+        let resolved = overrides.resolve(&specs, Part::One, PuzzleInputType::Example);
+        assert_eq!(resolved.get("connections"), Some("10"));
+        let resolved = overrides.resolve(&specs, Part::Two, PuzzleInputType::Example);
+        assert_eq!(resolved.get("connections"), None);
+
+        overrides.set("connections", "500");
+        let ctx = RunContext::new(PuzzleInputType::Actual).with_params(overrides.resolve(
+            &specs,
+            Part::One,
+            PuzzleInputType::Actual,
+        ));
+        assert_eq!(ctx.param::<usize>("connections").unwrap(), 500);
+        assert!(ctx.param::<usize>("missing").is_err());
+    }
+}
@@ -1,6 +1,10 @@
 use std::error::Error;
 
-use crate::{day::Solution, util::number::parse_u8_slice_to_i64};
+use crate::{
+    day::{Answer, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::number::parse_number,
+};
 
 use super::Day;
 
@@ -11,9 +15,10 @@ impl Solution for Day01 {
         1
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn Error>> {
-        let mut position = 50;
-        let mut zero_hit = 0;
+    type Input<'a> = Vec<(i64, i64)>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn Error>> {
+        let mut rotations = Vec::new();
         for chars in input.split(|&c| c == b'\n') {
             if chars.is_empty() {
                 continue;
@@ -21,31 +26,42 @@ impl Solution for Day01 {
             let direction = match chars[0] {
                 b'L' => -1,
                 b'R' => 1,
-                _ => return Err("Invalid direction".into()),
+                _ => {
+                    return Err(
+                        ParseError::at(input, &chars[..1], "expected a direction L or R").into(),
+                    );
+                }
             };
-            let steps = parse_u8_slice_to_i64(&chars[1..]);
+            rotations.push((direction, parse_number(input, &chars[1..])?));
+        }
+        Ok(rotations)
+    }
+
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn Error>> {
+        let mut position = 50;
+        let mut zero_hit = 0;
+        for &(direction, steps) in input {
             position += direction * steps;
             position = ((position % 100) + 100) % 100;
             if position == 0 {
                 zero_hit += 1;
             }
         }
-        Ok(zero_hit)
+        Ok(zero_hit.into())
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
+    fn run_part_2(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let mut position = 50;
         let mut zero_pass = 0;
-        for chars in input.split(|&c| c == b'\n') {
-            if chars.is_empty() {
-                continue;
-            }
-            let direction = match chars[0] {
-                b'L' => -1,
-                b'R' => 1,
-                _ => return Err("Invalid direction".into()),
-            };
-            let steps = parse_u8_slice_to_i64(&chars[1..]);
+        for &(direction, steps) in input {
             position = {
                 let mut pos = position;
 
@@ -72,9 +88,13 @@ impl Solution for Day01 {
         }
         Ok(zero_pass.into())
     }
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"L68
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+examples! {
+    example: r#"L68
 L30
 R48
 L5
@@ -83,32 +103,12 @@ L55
 L1
 L99
 R14
-L82"#,
-        )
-    }
+L82"# => {
+        Part::One => 3,
+        Part::Two => 6,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day01)
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 3);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 6);
-    }
-}
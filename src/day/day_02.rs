@@ -1,6 +1,7 @@
 use crate::{
-    day::{Day, Solution},
-    util::number::parse_u8_slice_to_i64,
+    day::{Answer, Day, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::number::parse_number,
 };
 use rayon::prelude::*;
 
@@ -56,63 +57,59 @@ impl Solution for Day02 {
     fn number(&self) -> u8 {
         2
     }
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let sum = input
+    type Input<'a> = Vec<(i64, i64)>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(input
             .trim_ascii_end()
             .split(|&c| c == b',')
-            .par_bridge()
             .map(|range| {
-                let dash = range.iter().position(|&c| c == b'-').unwrap();
-                let start: i64 = parse_u8_slice_to_i64(&range[..dash]);
-                let end: i64 = parse_u8_slice_to_i64(&range[dash + 1..]);
-                self.sum_invalid_ids(start, end)
+                let dash = range
+                    .iter()
+                    .position(|&c| c == b'-')
+                    .ok_or_else(|| ParseError::at(input, range, "expected a range like 11-22"))?;
+                let start: i64 = parse_number(input, &range[..dash])?;
+                let end: i64 = parse_number(input, &range[dash + 1..])?;
+                Ok((start, end))
             })
-            .sum();
-        Ok(sum)
+            .collect::<Result<_, ParseError>>()?)
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let sum = input
-            .trim_ascii_end()
-            .split(|&c| c == b',')
-            .par_bridge()
-            .map(|range| {
-                let dash = range.iter().position(|&c| c == b'-').unwrap();
-                let start: i64 = parse_u8_slice_to_i64(&range[..dash]);
-                let end: i64 = parse_u8_slice_to_i64(&range[dash + 1..]);
-                self.sum_repeated_invalid_ids(start, end)
-            })
-            .sum();
-        Ok(sum)
+            .par_iter()
+            .map(|&(start, end)| self.sum_invalid_ids(start, end))
+            .sum::<i64>();
+        Ok(sum.into())
+    }
+
+    fn run_part_2(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let sum = input
+            .par_iter()
+            .map(|&(start, end)| self.sum_repeated_invalid_ids(start, end))
+            .sum::<i64>();
+        Ok(sum.into())
     }
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"#,
-        )
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
 }
 
-pub fn day() -> Box<dyn Day> {
-    Box::new(Day02)
+examples! {
+    example: r#"11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"# => {
+        Part::One => 1227775554,
+        Part::Two => 4174379265,
+    },
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 1227775554);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 4174379265);
-    }
+pub fn day() -> Box<dyn Day> {
+    Box::new(Day02)
 }
@@ -1,6 +1,9 @@
 use std::cmp::max;
 
-use crate::day::{Day, Solution};
+use crate::{
+    day::{Answer, Day, Example, Part, RunContext, Solution},
+    error::ParseError,
+};
 use rayon::prelude::*;
 
 struct Day03;
@@ -24,10 +27,30 @@ impl Solution for Day03 {
     fn number(&self) -> u8 {
         3
     }
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
+    type Input<'a> = Vec<&'a [u8]>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        let banks: Vec<&[u8]> = input.split(|&c| c == b'\n').collect();
+        for bank in &banks {
+            if let Some(i) = bank.iter().position(|c| !c.is_ascii_digit()) {
+                return Err(ParseError::at(
+                    input,
+                    &bank[i..=i],
+                    "expected a battery joltage digit",
+                )
+                .into());
+            }
+        }
+        Ok(banks)
+    }
+
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let sum = input
-            .split(|&c| c == b'\n')
-            .par_bridge()
+            .par_iter()
             .map(|bank| {
                 let mut pair = (0, 0);
                 let mut iter = bank.iter().map(|c| c - b'0').rev();
@@ -46,12 +69,15 @@ impl Solution for Day03 {
                 (pair.0 * 10 + pair.1) as i64
             })
             .sum::<i64>();
-        Ok(sum)
+        Ok(sum.into())
     }
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
+    fn run_part_2(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let sum = input
-            .split(|&c| c == b'\n')
-            .par_bridge()
+            .par_iter()
             .map(|bank| {
                 let mut iter = bank.iter().map(|c| c - b'0').rev();
                 let mut selection = (&mut iter).take(12).collect::<Vec<u8>>();
@@ -60,42 +86,26 @@ impl Solution for Day03 {
                 selection
                     .iter()
                     .take(12)
-                    .fold(0u64, |acc, &d| acc * 10 + d as u64) as i64
+                    .fold(0u64, |acc, &d| acc * 10 + d as u64)
             })
-            .sum();
-        Ok(sum)
+            .sum::<u64>();
+        Ok(sum.into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"987654321111111
+}
+
+examples! {
+    example: r#"987654321111111
 811111111111119
 234234234234278
-818181911112111"#,
-        )
-    }
+818181911112111"# => {
+        Part::One => 357,
+        Part::Two => 3121910778619,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day03)
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 357);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 3121910778619);
-    }
-}
@@ -1,86 +1,25 @@
-use crate::day::{Day, Solution};
+use crate::{
+    day::{Answer, Day, Example, Part, RunContext, Solution},
+    util::grid::Grid,
+};
 use rayon::prelude::*;
 
 struct Day04;
 
-struct Map {
-    lines: Box<[Box<[CellState]>]>,
-    width: usize,
-    height: usize,
-    mask: Vec<Vec<bool>>,
-}
-
-impl Map {
-    fn from_input<'a>(input: &[u8]) -> Map {
-        let lines = input.split(|&c| c == b'\n').collect::<Box<[&[u8]]>>();
-        let height = lines.len();
-        let width = lines[0].len();
-        let lines = lines
-            .iter()
-            .map(|line| {
-                let cells: Box<[CellState]> = line
-                    .iter()
-                    .map(|&c| {
-                        if c == b'@' {
-                            CellState::Filled
-                        } else {
-                            CellState::Empty
-                        }
-                    })
-                    .collect::<Box<[CellState]>>();
-                cells
-            })
-            .collect::<Box<[Box<[CellState]>]>>();
-
-        Map {
-            lines,
-            width,
-            height,
-            mask: vec![vec![false; width]; height],
-        }
-    }
-}
-
-static DIRECTIONS: &[(isize, isize)] = &[
-    (-1, -1),
-    (0, -1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-    (0, 1),
-    (-1, 1),
-    (-1, 0),
-];
-
 #[derive(PartialEq, Eq, Clone, Copy)]
 enum CellState {
     Empty,
     Filled,
 }
 
-impl Day04 {
-    fn is_blocked(&self, map: &Map, x: usize, y: usize) -> bool {
-        let bx = x as isize;
-        let by = y as isize;
-        let mut count = 0;
-        for (dx, dy) in DIRECTIONS {
-            let nx = bx + dx;
-            let ny = by + dy;
-            if nx >= 0
-                && ny >= 0
-                && (nx as usize) < map.width
-                && (ny as usize) < map.height
-                && map.lines[ny as usize][nx as usize] == CellState::Filled
-                && !map.mask[ny as usize][nx as usize]
-            {
-                count += 1;
-            }
+type Map = Grid<CellState>;
 
-            if count >= 4 {
-                return true;
-            }
-        }
-        return false;
+impl Day04 {
+    fn is_blocked(&self, map: &Map, mask: &Grid<bool>, position: (usize, usize)) -> bool {
+        map.neighbours_8(position)
+            .filter(|&neighbour| map[neighbour] == CellState::Filled && !mask[neighbour])
+            .nth(3)
+            .is_some()
     }
 }
 
@@ -88,54 +27,76 @@ impl Solution for Day04 {
     fn number(&self) -> u8 {
         4
     }
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let map = Map::from_input(input.trim_ascii_end());
+    type Input<'a> = Map;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(Grid::parse(
+            input.trim_ascii_end(),
+            "'@' or '.'",
+            |c| match c {
+                b'@' => Some(CellState::Filled),
+                b'.' => Some(CellState::Empty),
+                _ => None,
+            },
+        )?)
+    }
 
-        let count = map
-            .lines
-            .par_iter()
-            .enumerate()
-            .map(|(y, line)| {
-                line.iter()
-                    .enumerate()
-                    .filter(|&(_, &c)| c == CellState::Filled)
-                    .filter(|(x, _)| !self.is_blocked(&map, *x, y))
+    fn run_part_1(
+        &self,
+        map: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mask = Grid::new(map.width(), map.height(), false);
+
+        let count = (0..map.height())
+            .into_par_iter()
+            .map(|y| {
+                (0..map.width())
+                    .filter(|&x| map[(x, y)] == CellState::Filled)
+                    .filter(|&x| !self.is_blocked(map, &mask, (x, y)))
                     .count() as i64
             })
             .sum::<i64>();
 
-        Ok(count)
+        Ok(count.into())
     }
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let mut map = Map::from_input(input.trim_ascii_end());
+    fn run_part_2(
+        &self,
+        map: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut mask = Grid::new(map.width(), map.height(), false);
         let mut total = 0;
         loop {
-            let moved = map
-                .lines
-                .par_iter()
-                .enumerate()
-                .flat_map_iter(|(y, line)| {
-                    let map = &map;
-                    line.iter()
-                        .enumerate()
-                        .filter(move |(x, c)| **c == CellState::Filled && !map.mask[y][*x])
-                        .filter(move |(x, _)| !self.is_blocked(&map, *x, y))
-                        .map(move |(x, _)| (x, y))
+            let moved = (0..map.height())
+                .into_par_iter()
+                .flat_map_iter(|y| {
+                    let mask = &mask;
+                    (0..map.width())
+                        .map(move |x| (x, y))
+                        .filter(move |&position| {
+                            map[position] == CellState::Filled && !mask[position]
+                        })
+                        .filter(move |&position| !self.is_blocked(map, mask, position))
                 })
                 .collect::<Box<_>>();
 
             if moved.len() == 0 {
-                return Ok(total);
+                return Ok(total.into());
             }
             total += moved.len() as i64;
-            for (x, y) in moved {
-                map.mask[y][x] = true;
+            for position in moved {
+                mask[position] = true;
             }
         }
     }
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"..@@.@@@@.
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+examples! {
+    example: r#"..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
 @.@@@@..@.
@@ -144,30 +105,12 @@ impl Solution for Day04 {
 .@.@.@.@@@
 @.@@@.@@@@
 .@@@@@@@@.
-@.@.@@@.@."#,
-        )
-    }
+@.@.@@@.@."# => {
+        Part::One => 13,
+        Part::Two => 43,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day04)
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 13);
-    }
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 43);
-    }
-}
@@ -1,6 +1,7 @@
 use crate::{
-    day::{Day, Solution},
-    util::number::parse_u8_slice_to_i64,
+    day::{Answer, Day, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::number::parse_number,
 };
 use rayon::prelude::*;
 
@@ -28,53 +29,61 @@ impl Solution for Day05 {
     fn number(&self) -> u8 {
         5
     }
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let (ranges, ingredients) = {
-            let mut ranges = Vec::new();
-            let mut ingredients = Vec::new();
-            let mut parsing_ranges = true;
-            for line in input.split(|&c| c == b'\n') {
-                if line.len() == 0 {
-                    parsing_ranges = false;
-                    continue;
-                }
-                if parsing_ranges {
-                    let dash_position = line.iter().position(|&c| c == b'-').unwrap();
-                    let start: i64 = parse_u8_slice_to_i64(&line[..dash_position]);
-                    let end: i64 = parse_u8_slice_to_i64(&line[dash_position + 1..]);
-                    ranges.push((start, end));
-                } else {
-                    let ingredient: i64 = parse_u8_slice_to_i64(line);
-                    ingredients.push(ingredient);
-                }
+    type Input<'a> = (Vec<(i64, i64)>, Vec<i64>);
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        let mut ranges = Vec::new();
+        let mut ingredients = Vec::new();
+        let mut parsing_ranges = true;
+        for line in input.split(|&c| c == b'\n') {
+            if line.len() == 0 {
+                parsing_ranges = false;
+                continue;
+            }
+            if parsing_ranges {
+                let dash_position = line
+                    .iter()
+                    .position(|&c| c == b'-')
+                    .ok_or_else(|| ParseError::at(input, line, "expected a range like 3-5"))?;
+                let start: i64 = parse_number(input, &line[..dash_position])?;
+                let end: i64 = parse_number(input, &line[dash_position + 1..])?;
+                ranges.push((start, end));
+            } else {
+                let ingredient: i64 = parse_number(input, line)?;
+                ingredients.push(ingredient);
             }
-            (ranges, ingredients)
-        };
+        }
+        Ok((ranges, ingredients))
+    }
+
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let (ranges, ingredients) = input;
 
         Ok(ingredients
             .par_iter()
             .filter(|&&i| ranges.iter().any(|r| r.0 <= i && i <= r.1))
-            .count() as i64)
+            .count()
+            .into())
     }
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let ranges = {
-            let mut ranges = Vec::new();
-            for line in input.split(|&c| c == b'\n') {
-                if line.len() == 0 {
-                    break;
-                }
-                let dash_position = line.iter().position(|&c| c == b'-').unwrap();
-                let start: i64 = parse_u8_slice_to_i64(&line[..dash_position]);
-                let end: i64 = parse_u8_slice_to_i64(&line[dash_position + 1..]);
-                ranges.push((start, end));
-            }
-            self.merge_overlaps(&mut ranges)
-        };
-        Ok(ranges.iter().map(|r| r.1 - r.0 + 1).sum())
+    fn run_part_2(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let ranges = self.merge_overlaps(&mut input.0.clone());
+        Ok(ranges.iter().map(|r| r.1 - r.0 + 1).sum::<i64>().into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"3-5
+}
+
+examples! {
+    example: r#"3-5
 10-14
 16-20
 12-18
@@ -84,32 +93,12 @@ impl Solution for Day05 {
 8
 11
 17
-32"#,
-        )
-    }
+32"# => {
+        Part::One => 3,
+        Part::Two => 14,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day05)
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 3);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 14);
-    }
-}
@@ -1,36 +1,155 @@
-use std::fmt;
-
-use crate::{day::Solution, util::number::parse_u8_slice_to_i64};
+use crate::{
+    day::{Answer, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::{
+        grid::Grid,
+        number::{parse_int, parse_number},
+    },
+};
 
 use super::Day;
 
 struct Day06;
 
-struct VerticalIter<'a, A: Copy + fmt::Debug> {
-    data: &'a [Box<[A]>],
-    col: usize,
-    row: usize,
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Add,
+    Multiply,
 }
 
-impl<A: Copy + fmt::Debug> VerticalIter<'_, A> {
-    fn new<'a>(data: &'a [Box<[A]>], col: usize) -> VerticalIter<'a, A> {
-        VerticalIter { data, col, row: 0 }
+impl Operator {
+    fn apply(self, numbers: impl Iterator<Item = i64>) -> i64 {
+        match self {
+            Operator::Add => numbers.sum(),
+            Operator::Multiply => numbers.product(),
+        }
     }
 }
 
-impl<'a, A: Copy + fmt::Debug> Iterator for VerticalIter<'a, A> {
-    type Item = A;
+/// The problems of the worksheet, each written in the columns from its
+/// operator up to the blank column before the next one.
+#[derive(Debug)]
+struct Worksheet {
+    operators: Vec<Operator>,
+    /// The numbers read row by row, with one column per problem.
+    numbers: Grid<i64>,
+    /// The numbers of each problem read column by column, from left to
+    /// right, each with its most significant digit on top.
+    column_numbers: Vec<Vec<i64>>,
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.row >= self.data.len() {
-            return None;
+impl Day06 {
+    fn parse_worksheet(input: &[u8]) -> Result<Worksheet, ParseError> {
+        let lines: Box<[&[u8]]> = input
+            .split(|&c| c == b'\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        let Some((&ops, rows)) = lines.split_last() else {
+            return Err(ParseError::at(input, input, "expected a worksheet"));
+        };
+        if rows.is_empty() {
+            return Err(ParseError::at(
+                input,
+                ops,
+                "expected numbers above the operators",
+            ));
         }
-        if self.col >= self.data[self.row].len() {
-            return None;
+        let starts: Vec<usize> = (0..ops.len()).filter(|&i| ops[i] != b' ').collect();
+        if starts.first() != Some(&0) {
+            return Err(ParseError::at(
+                input,
+                ops,
+                "expected an operator at the start of the row",
+            ));
         }
-        let value = self.data[self.row][self.col];
-        self.row += 1;
-        Some(value)
+
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut operators = Vec::with_capacity(starts.len());
+        let mut spans = Vec::with_capacity(starts.len());
+        for (i, &start) in starts.iter().enumerate() {
+            operators.push(match ops[start] {
+                b'+' => Operator::Add,
+                b'*' => Operator::Multiply,
+                _ => {
+                    return Err(ParseError::at(
+                        input,
+                        &ops[start..=start],
+                        "expected an operator + or *",
+                    ));
+                }
+            });
+            let end = match starts.get(i + 1) {
+                Some(&next) if next == start + 1 => {
+                    return Err(ParseError::at(
+                        input,
+                        &ops[next..=next],
+                        "expected a space before the next operator",
+                    ));
+                }
+                Some(&next) => next - 1,
+                None => width,
+            };
+            for row in rows {
+                if let Some(&c) = row.get(end)
+                    && c != b' '
+                {
+                    return Err(ParseError::at(
+                        input,
+                        &row[end..=end],
+                        "expected a blank column between problems",
+                    ));
+                }
+            }
+            spans.push(start..end);
+        }
+
+        let numbers = rows
+            .iter()
+            .flat_map(|row| spans.iter().map(move |span| (row, span)))
+            .map(|(row, span)| {
+                // Rows may lack trailing spaces, so their cells past the end
+                // count as blank.
+                let cells = &row[span.start.min(row.len())..span.end.min(row.len())];
+                let number = cells.trim_ascii();
+                if number.is_empty() {
+                    return Err(ParseError::at(input, cells, "expected a number"));
+                }
+                parse_number(input, number)
+            })
+            .collect::<Result<_, _>>()?;
+
+        // The columns of the digits, padded to the widest row.
+        let digits = Grid::from_fn(width, rows.len(), |x, y| {
+            rows[y].get(x).copied().unwrap_or(b' ')
+        })
+        .transposed();
+        let column_numbers = spans
+            .iter()
+            .map(|span| {
+                span.clone()
+                    .map(|x| {
+                        let column: Vec<u8> = digits
+                            .row(x)
+                            .iter()
+                            .copied()
+                            .filter(|c| !c.is_ascii_whitespace())
+                            .collect();
+                        // The digits are spread over the rows, so errors
+                        // point at the column's operator cell instead.
+                        parse_int(&column).map_err(|e| {
+                            let cell = &ops[x.min(ops.len())..(x + 1).min(ops.len())];
+                            ParseError::at(input, cell, format!("{} in this column", e))
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Worksheet {
+            operators,
+            numbers: Grid::from_cells(spans.len(), numbers),
+            column_numbers,
+        })
     }
 }
 
@@ -39,88 +158,53 @@ impl Solution for Day06 {
         6
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let mut rows = Vec::new();
-        for line in input.split(|&c| c == b'\n') {
-            let line = std::str::from_utf8(line)?.trim();
-            if line.is_empty() {
-                continue;
-            }
-            let nums = line.split_whitespace().collect::<Box<[&str]>>();
-            rows.push(nums);
-        }
-        let ops = rows.pop().unwrap();
-        Ok((0..ops.len()).fold(0i64, |acc, col| {
-            let column_iter = VerticalIter::new(&rows, col);
-            let mut column_values = column_iter.map(|s| s.parse::<i64>().unwrap());
-            let first_value = column_values.next().unwrap();
-            let result = match ops[col] {
-                "*" => column_values.fold(first_value, |a, b| a * b),
-                "+" => column_values.fold(first_value, |a, b| a + b),
-                _ => panic!("Unknown operation"),
-            };
-            acc + result
-        }))
+    type Input<'a> = Worksheet;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(Self::parse_worksheet(input)?)
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let chars = input
-            .split(|&c| c == b'\n')
-            .map(|line| line.iter().copied().collect::<Box<[u8]>>())
-            .collect::<Box<[Box<[u8]>]>>();
-        let ops = chars.last().ok_or("No input lines")?;
-        let left_edges: Box<_> = ops
-            .iter()
-            .enumerate()
-            .filter_map(|(i, &c)| {
-                if c == b'+' || c == b'*' {
-                    Some(i)
-                } else {
-                    None
-                }
-            })
-            .collect();
-        let ops = left_edges.iter().map(|&i| ops[i]).collect::<Box<[u8]>>();
+    fn run_part_1(
+        &self,
+        worksheet: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        Ok(worksheet
+            .numbers
+            .columns()
+            .zip(&worksheet.operators)
+            .map(|(numbers, operator)| operator.apply(numbers.copied()))
+            .sum::<i64>()
+            .into())
+    }
 
-        Ok(left_edges
+    fn run_part_2(
+        &self,
+        worksheet: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        Ok(worksheet
+            .column_numbers
             .iter()
-            .enumerate()
-            .map(|(i, &p)| {
-                let op = ops[i];
-                let column_end = if i + 1 < left_edges.len() {
-                    left_edges[i + 1] - 2
-                } else {
-                    chars[0].len() - 1
-                };
-                (p..=column_end)
-                    .map(|c| VerticalIter::new(&chars, c))
-                    .map(|col_iter| {
-                        let mut num_chars = Vec::new();
-                        for ch in col_iter {
-                            if ch.is_ascii_digit() {
-                                num_chars.push(ch);
-                            }
-                        }
-                        parse_u8_slice_to_i64(&num_chars)
-                    })
-                    .reduce(|acc, next| match op {
-                        b'*' => acc * next,
-                        b'+' => acc + next,
-                        _ => panic!("Unknown operation"),
-                    })
-                    .unwrap()
-            })
-            .sum())
+            .zip(&worksheet.operators)
+            .map(|(numbers, operator)| operator.apply(numbers.iter().copied()))
+            .sum::<i64>()
+            .into())
     }
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"123 328  51 64 
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+examples! {
+    example: r#"123 328  51 64
  45 64  387 23 
   6 98  215 314
-*   +   *   +  "#,
-        )
-    }
+*   +   *   +  "# => {
+        Part::One => 4277556,
+        Part::Two => 3263827,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
@@ -131,19 +215,38 @@ pub fn day() -> Box<dyn Day> {
 mod test {
     use super::*;
 
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 4277556);
+    fn error(input: &[u8]) -> (usize, usize, String) {
+        let error = Day06::parse_worksheet(input).err().unwrap();
+        (error.line, error.column, error.message)
     }
 
     #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 3263827);
+    fn malformed_worksheets() {
+        assert_eq!(error(b""), (1, 1, "expected a worksheet".into()));
+        assert_eq!(
+            error(b"+   *"),
+            (1, 1, "expected numbers above the operators".into())
+        );
+        assert_eq!(error(b"12 3x\n+  * "), (1, 4, "invalid digit 'x'".into()));
+        assert_eq!(
+            error(b"12 3\n12\n+  * "),
+            (2, 3, "expected a number".into())
+        );
+        assert_eq!(
+            error(b"12 34\n+ *  "),
+            (1, 2, "expected a blank column between problems".into())
+        );
+        assert_eq!(
+            error(b"12 34\n +  *"),
+            (2, 1, "expected an operator at the start of the row".into())
+        );
+        assert_eq!(
+            error(b"12 3\n1  4\n+  * "),
+            (3, 5, "expected a number in this column".into())
+        );
+        assert_eq!(
+            error(b"12 34\n+  -"),
+            (2, 4, "expected an operator + or *".into())
+        );
     }
 }
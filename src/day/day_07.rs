@@ -1,6 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::day::Solution;
+use crate::{
+    day::{Answer, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::grid::Grid,
+};
 
 use super::Day;
 
@@ -16,19 +20,37 @@ impl Solution for Day07 {
         7
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let mut energized = HashSet::new();
+    type Input<'a> = Grid<u8>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        let input = input.trim_ascii_end();
+        let map = Grid::parse(input, "'S', '^' or '.'", |c| {
+            b"S^.".contains(&c).then_some(c)
+        })?;
+        let last = map.width().saturating_sub(1);
+        let on_edge = |y| [0, last].iter().any(|&x| map.get((x, y)) == Some(&b'^'));
+        if let Some(y) = (0..map.height()).find(|&y| on_edge(y)) {
+            let row = input.split(|&c| c == b'\n').nth(y).unwrap();
+            return Err(ParseError::at(input, row, "splitter on the edge of the manifold").into());
+        }
+        Ok(map)
+    }
 
-        let map = input.split(|&c| c == b'\n').collect::<Box<_>>();
+    fn run_part_1(
+        &self,
+        map: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut energized = HashSet::new();
 
-        for (index, &char) in map[0].iter().enumerate() {
+        for (index, &char) in map.row(0).iter().enumerate() {
             if char == b'S' {
                 energized.insert(index);
             }
         }
 
         let mut split = 0;
-        map.iter().skip(2).for_each(|&row| {
+        map.rows().skip(2).for_each(|row| {
             let mut next_energized = HashSet::new();
             for (index, &char) in row.iter().enumerate() {
                 if char == b'^' && energized.contains(&index) {
@@ -41,17 +63,17 @@ impl Solution for Day07 {
             energized = next_energized.union(&energized).copied().collect();
         });
 
-        Ok(split)
+        Ok(split.into())
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let map = input
-            .trim_ascii_end()
-            .split(|&c| c == b'\n')
-            .collect::<Box<_>>();
+    fn run_part_2(
+        &self,
+        map: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let mut particles: Vec<Particle> = Vec::new();
 
-        for (index, &char) in map[0].iter().enumerate() {
+        for (index, &char) in map.row(0).iter().enumerate() {
             if char == b'S' {
                 particles.push(Particle {
                     position: index,
@@ -60,7 +82,7 @@ impl Solution for Day07 {
             }
         }
 
-        for &row in map.iter().skip(2) {
+        for row in map.rows().skip(2) {
             let mut next_particles: HashMap<usize, i64> = HashMap::new();
             for particle in particles.into_iter() {
                 if row[particle.position] == b'^' {
@@ -81,12 +103,16 @@ impl Solution for Day07 {
                 .collect();
         }
 
-        Ok(particles.iter().map(|p| p.multitude).sum())
+        Ok(particles.iter().map(|p| p.multitude).sum::<i64>().into())
     }
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#".......S.......
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+examples! {
+    example: r#".......S.......
 ...............
 .......^.......
 ...............
@@ -101,32 +127,12 @@ impl Solution for Day07 {
 ..^...^.....^..
 ...............
 .^.^.^.^.^...^.
-..............."#,
-        )
-    }
+..............."# => {
+        Part::One => 21,
+        Part::Two => 40,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day07)
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 21);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 40);
-    }
-}
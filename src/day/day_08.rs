@@ -1,39 +1,20 @@
-use std::collections::{HashMap, HashSet};
-
 use crate::{
-    day::{Solution, get_input_mode},
-    util::{input::PuzzleInputType, number::parse_u8_slice_to_i64},
+    day::{Answer, Example, ParamSpec, Part, RunContext, Solution},
+    error::ParseError,
+    util::{dsu::Dsu, number::integers},
 };
 
 use super::Day;
 
 struct Day08;
 
-#[derive(Clone, Hash, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 struct Point {
     x: i64,
     y: i64,
     z: i64,
 }
 
-impl PartialOrd for Point {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Point {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.x.cmp(&other.x) {
-            std::cmp::Ordering::Equal => match self.y.cmp(&other.y) {
-                std::cmp::Ordering::Equal => self.z.cmp(&other.z),
-                other => other,
-            },
-            other => other,
-        }
-    }
-}
-
 impl Point {
     fn dist(&self, other: &Point) -> i64 {
         (self.x - other.x).pow(2) + (self.y - other.y).pow(2) + (self.z - other.z).pow(2)
@@ -46,189 +27,113 @@ impl std::fmt::Debug for Point {
     }
 }
 
+/// A possible connection between the junction boxes at two indices.
 struct Connection {
-    point_a: Point,
-    point_b: Point,
+    a: usize,
+    b: usize,
+    dist: i64,
 }
 
-impl Connection {
-    fn dist(&self) -> i64 {
-        self.point_a.dist(&self.point_b)
-    }
-
-    fn new(point_a: &Point, point_b: &Point) -> Self {
-        debug_assert!(point_a <= point_b);
-        Connection {
-            point_a: point_a.clone(),
-            point_b: point_b.clone(),
-        }
-    }
+/// The junction boxes and all pairwise connections, sorted by length.
+struct Playground {
+    points: Vec<Point>,
+    connections: Vec<Connection>,
 }
 
-impl Day08 {}
-
 impl Solution for Day08 {
     fn number(&self) -> u8 {
         8
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let is_example = get_input_mode() == PuzzleInputType::Example;
+    type Input<'a> = Playground;
 
-        let input: Vec<Point> = input
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        let points: Vec<Point> = input
             .trim_ascii()
             .split(|&c| c == b'\n')
             .map(|line| {
-                let mut coords = line
-                    .split(|&c| c == b',')
-                    .map(|num| parse_u8_slice_to_i64(num));
-                Point {
-                    x: coords.next().unwrap(),
-                    y: coords.next().unwrap(),
-                    z: coords.next().unwrap(),
-                }
+                let coords = integers(line)
+                    .within(input)
+                    .collect::<Result<Vec<i64>, _>>()?;
+                let [x, y, z] = coords[..] else {
+                    return Err(ParseError::at(input, line, "expected three coordinates"));
+                };
+                Ok(Point { x, y, z })
             })
-            .collect();
-
-        let mut connections: Vec<Connection> = input
-            .iter()
-            .flat_map(|p1| {
-                input
-                    .iter()
-                    .cloned()
-                    .filter(move |p2| p1 < p2)
-                    .map(|p2| Connection::new(p1, &p2))
+            .collect::<Result<_, ParseError>>()?;
+
+        let mut connections: Vec<Connection> = (0..points.len())
+            .flat_map(|a| (a + 1..points.len()).map(move |b| (a, b)))
+            .map(|(a, b)| Connection {
+                a,
+                b,
+                dist: points[a].dist(&points[b]),
             })
             .collect();
-        connections.sort_by_key(|c| c.dist());
-
-        let mut component: HashMap<&Point, usize> = HashMap::new();
-
-        for connection in connections.iter().take(if is_example { 10 } else { 1000 }) {
-            let component_id_a = component.get(&connection.point_a);
-            let component_id_b = component.get(&connection.point_b);
+        connections.sort_by_key(|c| c.dist);
 
-            match (component_id_a, component_id_b) {
-                (Some(&id_a), Some(&id_b)) => {
-                    if id_a == id_b {
-                        continue;
-                    }
-                    let new_id = id_a.min(id_b);
-                    let old_id = id_a.max(id_b);
+        Ok(Playground {
+            points,
+            connections,
+        })
+    }
 
-                    for (_point, comp_id) in component.iter_mut() {
-                        if *comp_id == old_id {
-                            *comp_id = new_id;
-                        }
-                    }
-                }
-                (Some(&id), None) => {
-                    component.insert(&connection.point_b, id);
-                }
-                (None, Some(&id)) => {
-                    component.insert(&connection.point_a, id);
-                }
-                (None, None) => {
-                    let new_id = component.values().max().unwrap_or(&0) + 1;
-                    component.insert(&connection.point_a, new_id);
-                    component.insert(&connection.point_b, new_id);
-                }
-            }
-        }
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let connections: usize = ctx.param("connections")?;
 
-        let mut by_component: HashMap<usize, Vec<&Point>> = HashMap::new();
-        for (point, &comp_id) in component.iter() {
-            by_component.entry(comp_id).or_default().push(point);
+        let mut circuits = Dsu::new(input.points.len());
+        for connection in input.connections.iter().take(connections) {
+            circuits.union(connection.a, connection.b);
         }
 
-        let mut component_sizes: Vec<i64> =
-            by_component.iter().map(|(_, v)| v.len() as i64).collect();
-        component_sizes.sort();
-
-        Ok(component_sizes
+        Ok(circuits
+            .component_sizes()
             .into_iter()
-            .rev()
             .take(3)
-            .reduce(|acc, next| acc * next)
-            .unwrap())
+            .product::<usize>()
+            .into())
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let input: Vec<Point> = input
-            .trim_ascii()
-            .split(|&c| c == b'\n')
-            .map(|line| {
-                let mut coords = line
-                    .split(|&c| c == b',')
-                    .map(|num| parse_u8_slice_to_i64(num));
-                Point {
-                    x: coords.next().unwrap(),
-                    y: coords.next().unwrap(),
-                    z: coords.next().unwrap(),
-                }
-            })
-            .collect();
-
-        let mut connections: Vec<Connection> = input
+    fn run_part_2(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut circuits = Dsu::new(input.points.len());
+        let last_added_connection = input
+            .connections
             .iter()
-            .flat_map(|p1| {
-                input
-                    .iter()
-                    .cloned()
-                    .filter(move |p2| p1 < p2)
-                    .map(|p2| Connection::new(p1, &p2))
+            .find(|connection| {
+                circuits.union(connection.a, connection.b) && circuits.components() == 1
             })
-            .collect();
-        connections.sort_by_key(|c| c.dist());
-
-        let mut component: HashMap<&Point, usize> = HashMap::new();
-        let mut last_added_connection = None;
+            .ok_or("The junction boxes never form a single circuit")?;
 
-        let mut connection_iter = connections.iter();
-        while component.values().collect::<HashSet<_>>().len() != 1 || component.len() < input.len()
-        {
-            let connection = connection_iter.next().unwrap();
-            let component_id_a = component.get(&connection.point_a);
-            let component_id_b = component.get(&connection.point_b);
-
-            last_added_connection = Some(connection);
-
-            match (component_id_a, component_id_b) {
-                (Some(&id_a), Some(&id_b)) => {
-                    if id_a == id_b {
-                        continue;
-                    }
-                    let new_id = id_a.min(id_b);
-                    let old_id = id_a.max(id_b);
+        Ok(
+            (input.points[last_added_connection.a].x * input.points[last_added_connection.b].x)
+                .into(),
+        )
+    }
 
-                    for (_point, comp_id) in component.iter_mut() {
-                        if *comp_id == old_id {
-                            *comp_id = new_id;
-                        }
-                    }
-                }
-                (Some(&id), None) => {
-                    component.insert(&connection.point_b, id);
-                }
-                (None, Some(&id)) => {
-                    component.insert(&connection.point_a, id);
-                }
-                (None, None) => {
-                    let new_id = component.values().max().unwrap_or(&0) + 1;
-                    component.insert(&connection.point_a, new_id);
-                    component.insert(&connection.point_b, new_id);
-                }
-            }
-        }
+    fn params(&self) -> &'static [ParamSpec] {
+        &[ParamSpec {
+            name: "connections",
+            parts: &[Part::One],
+            example: "10",
+            actual: "1000",
+        }]
+    }
 
-        Ok(last_added_connection
-            .map(|c| c.point_a.x * c.point_b.x)
-            .unwrap())
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
+}
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"162,817,812
+examples! {
+    example: r#"162,817,812
 57,618,57
 906,360,560
 592,479,940
@@ -247,35 +152,12 @@ impl Solution for Day08 {
 941,993,340
 862,61,35
 984,92,344
-425,690,689"#,
-        )
-    }
+425,690,689"# => {
+        Part::One => 40,
+        Part::Two => 25272,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day08)
 }
-
-#[cfg(test)]
-mod test {
-    use crate::day::set_input_mode;
-
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        set_input_mode(PuzzleInputType::Example);
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 40);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 25272);
-    }
-}
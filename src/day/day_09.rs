@@ -1,6 +1,10 @@
 use std::cmp::{max, min};
 
-use crate::{day::Solution, util::number::parse_u8_slice_to_i64};
+use crate::{
+    day::{Answer, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::number::integers,
+};
 
 use super::Day;
 
@@ -49,19 +53,29 @@ impl Solution for Day09 {
         9
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let tiles: Vec<_> = input
+    type Input<'a> = Vec<(i64, i64)>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(input
             .trim_ascii_end()
             .split(|&c| c == b'\n')
             .map(|line| {
-                let mut parts = line.split(|&c| c == b',');
-                (
-                    parse_u8_slice_to_i64(parts.next().unwrap()),
-                    parse_u8_slice_to_i64(parts.next().unwrap()),
-                )
+                let coords = integers(line)
+                    .within(input)
+                    .collect::<Result<Vec<i64>, _>>()?;
+                let [x, y] = coords[..] else {
+                    return Err(ParseError::at(input, line, "expected two coordinates"));
+                };
+                Ok((x, y))
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?)
+    }
 
+    fn run_part_1(
+        &self,
+        tiles: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let tile_pairs = tiles.iter().flat_map(|t1| {
             tiles.iter().filter_map(move |t2| {
                 if t1 >= t2 {
@@ -75,22 +89,15 @@ impl Solution for Day09 {
         Ok(tile_pairs
             .map(|((x1, y1), (x2, y2))| ((y2 - y1).abs() + 1) * ((x2 - x1).abs() + 1))
             .max()
-            .unwrap())
+            .unwrap()
+            .into())
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let tiles: Vec<_> = input
-            .trim_ascii_end()
-            .split(|&c| c == b'\n')
-            .map(|line| {
-                let mut parts = line.split(|&c| c == b',');
-                (
-                    parse_u8_slice_to_i64(parts.next().unwrap()),
-                    parse_u8_slice_to_i64(parts.next().unwrap()),
-                )
-            })
-            .collect();
-
+    fn run_part_2(
+        &self,
+        tiles: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         let mut edges = Vec::with_capacity(tiles.len() + 1);
         for i in 0..tiles.len() {
             edges.push(Edge {
@@ -123,44 +130,29 @@ impl Solution for Day09 {
         Ok(tile_pairs
             .map(|((x1, y1), (x2, y2))| ((y2 - y1).abs() + 1) * ((x2 - x1).abs() + 1))
             .max()
-            .unwrap())
+            .unwrap()
+            .into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
+}
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"7,1
+examples! {
+    example: r#"7,1
 11,1
 11,7
 9,7
 9,5
 2,5
 2,3
-7,3"#,
-        )
-    }
+7,3"# => {
+        Part::One => 50,
+        Part::Two => 24,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
     Box::new(Day09)
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 50);
-    }
-
-    #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 24);
-    }
-}
@@ -1,9 +1,17 @@
 use std::collections::VecDeque;
 
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+#[cfg(feature = "z3")]
 use z3::{Optimize, ast::Int};
 
-use crate::{day::Solution, util::number::parse_u8_slice_to_i64};
+use crate::{
+    day::{Answer, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::{
+        ilp::{self, SolveError},
+        number::integers,
+    },
+};
 
 use super::Day;
 
@@ -18,7 +26,7 @@ struct Machine {
 }
 
 impl Machine {
-    fn from_input(line: &[u8]) -> Self {
+    fn from_input(input: &[u8], line: &[u8]) -> Result<Self, ParseError> {
         let parts = line.split(|&c| c == b' ');
         let mut target_state = None;
         let mut buttons = Vec::new();
@@ -26,37 +34,58 @@ impl Machine {
         for part in parts {
             if part.starts_with(b"[") && part.ends_with(b"]") {
                 let state = &part[1..part.len() - 1];
-                target_state = Some(
-                    state
-                        .iter()
-                        .map(|&c| match c {
-                            b'#' => true,
-                            b'.' => false,
-                            _ => panic!("Invalid state character"),
-                        })
-                        .collect::<Box<[bool]>>(),
-                );
+                if let Some(i) = state.iter().position(|&c| c != b'#' && c != b'.') {
+                    return Err(ParseError::at(input, &state[i..=i], "expected '#' or '.'"));
+                }
+                target_state = Some(state.iter().map(|&c| c == b'#').collect::<Box<[bool]>>());
             } else if part.starts_with(b"(") && part.ends_with(b")") {
                 let button = &part[1..part.len() - 1];
-                let button_read = button
-                    .split(|&c| c == b',')
-                    .map(|idx| parse_u8_slice_to_i64(idx) as usize)
-                    .collect::<Box<[usize]>>();
-                buttons.push(button_read);
+                let button_read = integers(button)
+                    .within(input)
+                    .collect::<Result<Box<[usize]>, ParseError>>()?;
+                buttons.push((part, button_read));
             } else if part.starts_with(b"{") && part.ends_with(b"}") {
                 let joltage = &part[1..part.len() - 1];
-                let joltage_read = joltage
-                    .split(|&c| c == b',')
-                    .map(|idx| parse_u8_slice_to_i64(idx) as usize)
-                    .collect::<Box<[usize]>>();
-                joltages = Some(joltage_read);
+                let joltage_read = integers(joltage)
+                    .within(input)
+                    .collect::<Result<Box<[usize]>, ParseError>>()?;
+                joltages = Some((part, joltage_read));
+            } else {
+                return Err(ParseError::at(
+                    input,
+                    part,
+                    "expected [lights], (button) or {joltages}",
+                ));
             }
         }
-        Machine {
-            target_lights: target_state.unwrap(),
-            buttons: buttons.into_boxed_slice(),
-            target_joltage: joltages.unwrap(),
+        let target_lights =
+            target_state.ok_or_else(|| ParseError::at(input, line, "missing [lights]"))?;
+        let (joltage_part, target_joltage) =
+            joltages.ok_or_else(|| ParseError::at(input, line, "missing {joltages}"))?;
+        if target_joltage.len() != target_lights.len() {
+            return Err(ParseError::at(
+                input,
+                joltage_part,
+                format!("expected {} joltages", target_lights.len()),
+            ));
+        }
+        for (part, button) in &buttons {
+            if button.iter().any(|&light| light >= target_lights.len()) {
+                return Err(ParseError::at(
+                    input,
+                    part,
+                    format!(
+                        "button for a light beyond the {} lights",
+                        target_lights.len()
+                    ),
+                ));
+            }
         }
+        Ok(Machine {
+            target_lights,
+            buttons: buttons.into_iter().map(|(_, button)| button).collect(),
+            target_joltage,
+        })
     }
 
     fn initial_lights_state(&self) -> Box<[bool]> {
@@ -87,7 +116,37 @@ impl Machine {
         unreachable!()
     }
 
-    fn bfs_least_buttons_joltage(&self) -> usize {
+    /// The fewest button presses that reach the target joltages, if any do.
+    fn least_buttons_joltage(&self) -> Result<Option<usize>, SolveError> {
+        #[cfg(feature = "z3")]
+        return Ok(self.least_buttons_joltage_z3());
+        #[cfg(not(feature = "z3"))]
+        return self.least_buttons_joltage_native();
+    }
+
+    #[cfg_attr(feature = "z3", allow(unused))]
+    fn least_buttons_joltage_native(&self) -> Result<Option<usize>, SolveError> {
+        // One equation per counter, summing the presses of the buttons that
+        // increase it.
+        let matrix: Vec<Vec<u64>> = (0..self.target_joltage.len())
+            .map(|counter| {
+                self.buttons
+                    .iter()
+                    .map(|button| button.contains(&counter) as u64)
+                    .collect()
+            })
+            .collect();
+        let targets: Vec<u64> = self
+            .target_joltage
+            .iter()
+            .map(|&joltage| joltage as u64)
+            .collect();
+        let presses = ilp::minimize_sum(&matrix, &targets)?;
+        Ok(presses.map(|presses| presses.iter().sum::<u64>() as usize))
+    }
+
+    #[cfg(feature = "z3")]
+    fn least_buttons_joltage_z3(&self) -> Option<usize> {
         let optimize = Optimize::new();
         let btn_presses: Vec<Int> = (0..self.buttons.len())
             .map(|i| Int::fresh_const(format!("btn_{}", i).as_str()))
@@ -115,12 +174,9 @@ impl Machine {
 
         optimize.check(&[]);
 
-        if let Some(model) = optimize.get_model() {
-            let total: i64 = model.eval(&total_presses, true).unwrap().as_i64().unwrap();
-            return total as usize;
-        }
-
-        unreachable!()
+        let model = optimize.get_model()?;
+        let total = model.eval(&total_presses, true)?.as_i64()?;
+        Some(total as usize)
     }
 }
 
@@ -129,41 +185,57 @@ impl Solution for Day10 {
         10
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let machines: Vec<Machine> = input
+    type Input<'a> = Vec<Machine>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(input
             .trim_ascii_end()
             .split(|&c| c == b'\n')
-            .map(|line| Machine::from_input(line))
-            .collect();
+            .map(|line| Machine::from_input(input, line))
+            .collect::<Result<_, ParseError>>()?)
+    }
 
+    fn run_part_1(
+        &self,
+        machines: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         Ok(machines
             .par_iter()
-            .map(|m| m.bfs_least_buttons_lights() as i64)
-            .sum())
+            .map(|m| m.bfs_least_buttons_lights())
+            .sum::<usize>()
+            .into())
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let machines: Vec<Machine> = input
-            .trim_ascii_end()
-            .split(|&c| c == b'\n')
-            .map(|line| Machine::from_input(line))
-            .collect();
-
+    fn run_part_2(
+        &self,
+        machines: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
         Ok(machines
             .par_iter()
-            .map(|m| m.bfs_least_buttons_joltage() as i64)
-            .sum())
+            .map(|m| m.least_buttons_joltage())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .sum::<Option<usize>>()
+            .ok_or("A machine cannot reach its target joltages")?
+            .into())
     }
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
-[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
-[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}"#,
-        )
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
 }
 
+examples! {
+    example: r#"[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}"# => {
+        Part::One => 7,
+        Part::Two => 33,
+    },
+}
+
 pub fn day() -> Box<dyn Day> {
     Box::new(Day10)
 }
@@ -172,19 +244,52 @@ pub fn day() -> Box<dyn Day> {
 mod test {
     use super::*;
 
+    fn machines(input: &str) -> Vec<Machine> {
+        Day10.parse(input.as_bytes()).unwrap()
+    }
+
     #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 7);
+    fn unreachable_joltages() {
+        // Both counters only ever increase together.
+        let machines = machines("[..] (0,1) {1,2}");
+        assert_eq!(machines[0].least_buttons_joltage_native(), Ok(None));
+        assert!(Day10.run_part_2(&machines, &RunContext::example()).is_err());
     }
 
+    #[cfg(feature = "z3")]
     #[test]
-    fn part_2_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_2(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 33);
+    fn backends_agree() {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut random = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n) as usize
+        };
+        let mut machines = machines(EXAMPLES[0].input);
+        for _ in 0..100 {
+            let counters = 2 + random(6);
+            let buttons: Vec<Box<[usize]>> = (0..1 + random(8))
+                .map(|_| (0..counters).filter(|_| random(3) == 0).collect())
+                .collect();
+            let mut target_joltage = vec![0; counters];
+            for button in &buttons {
+                let presses = random(20);
+                button
+                    .iter()
+                    .for_each(|&counter| target_joltage[counter] += presses);
+            }
+            machines.push(Machine {
+                target_lights: vec![false; counters].into(),
+                buttons: buttons.into(),
+                target_joltage: target_joltage.into(),
+            });
+        }
+        for machine in &machines {
+            assert_eq!(
+                machine.least_buttons_joltage_native(),
+                Ok(machine.least_buttons_joltage_z3())
+            );
+        }
     }
 }
@@ -1,176 +1,189 @@
-use std::{collections::HashMap, mem::swap};
-
-use matrixmultiply::sgemm;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::{collections::HashSet, error::Error};
 
 use crate::{
-    day::{Solution, get_input_mode},
-    util::input::PuzzleInputType,
+    day::{Answer, Example, ParamSpec, Part, RunContext, Solution},
+    error::ParseError,
+    util::graph::{Graph, NodeId},
 };
 
 use super::Day;
 
 struct Day11;
 
-impl Day11 {
-    fn parse_input(input: &[u8]) -> HashMap<&[u8], Vec<&[u8]>> {
-        let mut graph = HashMap::new();
-        for line in input.split(|&c| c == b'\n').filter(|l| !l.is_empty()) {
-            let parts: Vec<&[u8]> = line.split(|&c| c == b':').collect();
-            let node = parts[0];
-            let edges = if parts.len() > 1 {
-                parts[1]
-                    .split(|&c| c == b' ')
-                    .filter(|s| !s.is_empty())
-                    .collect()
-            } else {
-                Vec::new()
-            };
-            graph.insert(node, edges);
-        }
-        graph
-    }
-
-    fn count_paths_adj<'a>(
-        from: &'a [u8],
-        to: &'a [u8],
-        graph: &'a HashMap<&[u8], Vec<&[u8]>>,
-    ) -> i64 {
-        let mut adjacency_list: Vec<Vec<f32>> = Vec::new();
-        let ordering = {
-            let mut ordering = graph.keys().cloned().collect::<Vec<_>>();
-            ordering.push(b"out");
-            ordering
-        };
-
-        for &node in ordering.iter() {
-            let mut edges = Vec::new();
-            for &other_node in ordering.iter() {
-                if let Some(neighbors) = graph.get(node) {
-                    if neighbors.contains(&other_node) {
-                        edges.push(1.0);
-                    } else {
-                        edges.push(0.0);
-                    }
-                } else {
-                    edges.push(0.0);
-                }
-            }
-            adjacency_list.push(edges);
-        }
-
-        let from_index = ordering.iter().position(|&n| n == from).unwrap();
-        let to_index = ordering.iter().position(|&n| n == to).unwrap();
-
-        let n = ordering.len();
-        let mut count = 0.0;
-        let adjacency_matrix = adjacency_list.concat();
-        let mut adjacency_matrix_squared = adjacency_matrix.clone();
-        let mut temp = vec![0.0; n * n];
-        loop {
-            if (adjacency_matrix_squared[from_index * n + to_index]) > 0.0 {
-                count += adjacency_matrix_squared[from_index * n + to_index];
-            }
-
-            if adjacency_matrix_squared.iter().all(|&x| x == 0.0) {
-                break;
-            }
+/// The parsed device graph, with problems worth pointing out that do not
+/// stop the paths from being counted.
+struct Devices<'a> {
+    graph: Graph<'a>,
+    warnings: Vec<String>,
+}
 
-            unsafe {
-                sgemm(
-                    n,
-                    n,
-                    n,
-                    1.0,
-                    adjacency_matrix_squared.as_ptr(),
-                    n as isize,
-                    1,
-                    adjacency_matrix.as_ptr(),
-                    n as isize,
-                    1,
-                    0.0,
-                    temp.as_mut_ptr(),
-                    n as isize,
-                    1,
-                );
-            }
-            swap(&mut adjacency_matrix_squared, &mut temp);
-        }
+impl Devices<'_> {
+    fn device(&self, name: &str) -> Result<NodeId, String> {
+        self.graph
+            .id(name.as_bytes())
+            .ok_or_else(|| format!("No device {}", name))
+    }
 
-        count as i64
+    /// The devices in a comma separated list like `dac,fft`.
+    fn device_list(&self, names: &str) -> Result<Vec<NodeId>, String> {
+        names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| self.device(name))
+            .collect()
     }
 
+    /// Counts the paths from `from` to `to` that pass all devices listed in
+    /// `via`, in any order, and none listed in `avoid`.
     fn count_paths(
-        from: &[u8],
-        to: &[u8],
-        graph: &HashMap<&[u8], Vec<&[u8]>>,
-        must_not: Option<&[&[u8]]>,
-    ) -> i64 {
-        let mut path = vec![from];
-
-        fn explore<'a>(
-            path: &mut Vec<&'a [u8]>,
-            graph: &HashMap<&[u8], Vec<&'a [u8]>>,
-            end: &[u8],
-            must_not: Option<&[&'a [u8]]>,
-        ) -> i64 {
-            let last = path.last().unwrap();
-            if last == &end {
-                return 1;
-            }
+        &self,
+        from: &str,
+        to: &str,
+        via: &str,
+        avoid: &str,
+    ) -> Result<u128, Box<dyn Error>> {
+        let (from, to) = (self.device(from)?, self.device(to)?);
+        let (via, avoid) = (self.device_list(via)?, self.device_list(avoid)?);
+        Ok(self.graph.count_paths_visiting(from, to, &via, &avoid)?)
+    }
+}
 
-            let neighbors = match graph.get(last) {
-                Some(n) => n,
-                None => return 0,
-            };
+fn device_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).into_owned()
+}
 
-            let mut count = 0;
-            for &neighbor in neighbors.iter() {
-                if path.contains(&neighbor) {
-                    continue;
-                }
-                if let Some(must_not_list) = must_not {
-                    if must_not_list.contains(&neighbor) {
-                        continue;
-                    }
-                }
+/// Names a list of devices, abbreviating long ones.
+fn list_devices(names: &[&[u8]]) -> String {
+    let mut listed: Vec<String> = names
+        .iter()
+        .take(10)
+        .map(|name| device_name(name))
+        .collect();
+    if names.len() > listed.len() {
+        listed.push(format!("and {} more", names.len() - listed.len()));
+    }
+    listed.join(", ")
+}
 
-                path.push(neighbor);
-                count += explore(path, graph, end, must_not);
-                path.pop();
+impl Day11 {
+    fn parse_input(input: &[u8]) -> Result<Devices<'_>, ParseError> {
+        let mut graph = Graph::new();
+        let mut listed = HashSet::new();
+        for line in input.split(|&c| c == b'\n').filter(|l| !l.is_empty()) {
+            let colon = line.iter().position(|&c| c == b':').ok_or_else(|| {
+                ParseError::at(input, line, "expected a device like `aaa: bbb ccc`")
+            })?;
+            let node = &line[..colon];
+            let edges: Vec<&[u8]> = line[colon + 1..]
+                .split(|&c| c == b' ')
+                .filter(|s| !s.is_empty())
+                .collect();
+            for &name in std::iter::once(&node).chain(&edges) {
+                if name.is_empty() || !name.iter().all(u8::is_ascii_alphanumeric) {
+                    return Err(ParseError::at(input, name, "expected a device name"));
+                }
+            }
+            if !listed.insert(node) {
+                return Err(ParseError::at(input, node, "device listed twice"));
             }
+            let node = graph.node(node);
+            for edge in edges {
+                let edge = graph.node(edge);
+                graph.add_edge(node, edge);
+            }
+        }
+        let warnings = Self::validate(input, &graph, &listed)?;
+        Ok(Devices { graph, warnings })
+    }
 
-            count
+    /// Rejects cycles, which allow endless paths, and describes devices
+    /// that cannot be reached and outputs that lead nowhere.
+    fn validate(
+        input: &[u8],
+        graph: &Graph,
+        listed: &HashSet<&[u8]>,
+    ) -> Result<Vec<String>, ParseError> {
+        let cycles = graph.cycles();
+        if let Some(first) = cycles.first() {
+            let names: Vec<String> = cycles
+                .iter()
+                .map(|cycle| {
+                    let names: Vec<String> = cycle
+                        .iter()
+                        .chain(&cycle[..1])
+                        .map(|&id| device_name(graph.label(id)))
+                        .collect();
+                    names.join(" -> ")
+                })
+                .collect();
+            return Err(ParseError::at(
+                input,
+                // Devices on a cycle have outputs, so they are listed.
+                listed.get(graph.label(first[0])).unwrap(),
+                match names.len() {
+                    1 => format!("devices form a cycle {}", names[0]),
+                    n => format!("devices form {} cycles: {}", n, names.join(", ")),
+                },
+            ));
         }
 
-        explore(&mut path, graph, to, must_not)
-    }
+        let mut warnings = Vec::new();
+        let dangling: Vec<&[u8]> = graph
+            .nodes()
+            .map(|id| graph.label(id))
+            .filter(|&name| name != b"out" && !listed.contains(name))
+            .collect();
+        if !dangling.is_empty() {
+            warnings.push(format!(
+                "Outputs that are not devices: {}",
+                list_devices(&dangling)
+            ));
+        }
 
-    fn count_you_to_out(graph: &HashMap<&[u8], Vec<&[u8]>>) -> i64 {
-        Self::count_paths(b"you", b"out", graph, None)
+        let entries: Vec<NodeId> = [&b"you"[..], b"svr"]
+            .into_iter()
+            .filter_map(|entry| graph.id(entry))
+            .collect();
+        if !entries.is_empty() {
+            let reached = graph.reachable(entries.iter().copied());
+            let unreachable: Vec<&[u8]> = graph
+                .nodes()
+                .filter(|id| !reached[id.index()])
+                .map(|id| graph.label(id))
+                .collect();
+            if !unreachable.is_empty() {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|&entry| device_name(graph.label(entry)))
+                    .collect();
+                warnings.push(format!(
+                    "Devices unreachable from {}: {}",
+                    entries.join(" or "),
+                    list_devices(&unreachable)
+                ));
+            }
+        }
+        Ok(warnings)
     }
 
-    fn count_srv_to_out_over_dac_and_fft(graph: &HashMap<&[u8], Vec<&[u8]>>) -> i64 {
-        [(b"svr", b"fft"), (b"fft", b"dac"), (b"dac", b"out")]
-            .par_iter()
-            .map(|&(from, to)| Self::count_paths_adj(from, to, graph))
-            .product()
+    /// Logs the warnings about the devices. Both parts run on the same
+    /// input, so only part 1 calls this to log them once.
+    fn warn(devices: &Devices, ctx: &RunContext) {
+        if !ctx.is_benchmarking {
+            for warning in &devices.warnings {
+                ctx.log(&format!("Warning: {}", warning));
+            }
+        }
     }
 
-    fn get_exmaple_part_2(&self) -> &'static str {
-        "svr: aaa bbb
-aaa: fft
-fft: ccc
-bbb: tty
-tty: ccc
-ccc: ddd eee
-ddd: hub
-hub: fff
-eee: dac
-dac: fff
-fff: ggg hhh
-ggg: out
-hhh: out"
+    /// Counts the paths the parameters of the running part ask for.
+    fn count_paths(devices: &Devices, ctx: &RunContext) -> Result<u128, Box<dyn Error>> {
+        let from: String = ctx.param("from")?;
+        let to: String = ctx.param("to")?;
+        let via: String = ctx.param("via")?;
+        let avoid: String = ctx.param("avoid")?;
+        devices.count_paths(&from, &to, &via, &avoid)
     }
 }
 
@@ -179,26 +192,77 @@ impl Solution for Day11 {
         11
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let graph = Self::parse_input(input);
-        let result = Self::count_you_to_out(&graph);
-        Ok(result)
+    type Input<'a> = Devices<'a>;
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(Self::parse_input(input)?)
+    }
+
+    fn run_part_1(
+        &self,
+        devices: &Self::Input<'_>,
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        Self::warn(devices, ctx);
+        Ok(Self::count_paths(devices, ctx)?.into())
     }
 
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let graph = if get_input_mode() == PuzzleInputType::Example {
-            let example_input = self.get_exmaple_part_2();
-            Self::parse_input(example_input.as_bytes())
-        } else {
-            Self::parse_input(input)
-        };
-        let result = Self::count_srv_to_out_over_dac_and_fft(&graph);
-        Ok(result)
+    fn run_part_2(
+        &self,
+        devices: &Self::Input<'_>,
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        Ok(Self::count_paths(devices, ctx)?.into())
     }
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"aaa: you hhh
+    fn params(&self) -> &'static [ParamSpec] {
+        &[
+            ParamSpec {
+                name: "from",
+                parts: &[Part::One],
+                example: "you",
+                actual: "you",
+            },
+            ParamSpec {
+                name: "from",
+                parts: &[Part::Two],
+                example: "svr",
+                actual: "svr",
+            },
+            ParamSpec {
+                name: "to",
+                parts: &[Part::One, Part::Two],
+                example: "out",
+                actual: "out",
+            },
+            ParamSpec {
+                name: "via",
+                parts: &[Part::One],
+                example: "",
+                actual: "",
+            },
+            ParamSpec {
+                name: "via",
+                parts: &[Part::Two],
+                example: "dac,fft",
+                actual: "dac,fft",
+            },
+            ParamSpec {
+                name: "avoid",
+                parts: &[Part::One, Part::Two],
+                example: "",
+                actual: "",
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+examples! {
+    you_to_out: r#"aaa: you hhh
 you: bbb ccc
 bbb: ddd eee
 ccc: ddd eee fff
@@ -207,9 +271,24 @@ eee: out
 fff: out
 ggg: out
 hhh: ccc fff iii
-iii: out"#,
-        )
-    }
+iii: out"# => {
+        Part::One => 5,
+    },
+    svr_through_dac_and_fft: r#"svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out"# => {
+        Part::Two => 2,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
@@ -218,23 +297,81 @@ pub fn day() -> Box<dyn Day> {
 
 #[cfg(test)]
 mod test {
-    use crate::day::set_input_mode;
-
     use super::*;
 
     #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 5);
+    fn counts_more_paths_than_floats_hold() {
+        // 20 layers of three devices between svr and fft, each connected to
+        // all devices of the next layer.
+        let layer = |i: usize| format!("l{}a l{}b l{}c", i, i, i);
+        let mut input = format!("svr: {}\n", layer(0));
+        for i in 0..20 {
+            let next = if i == 19 {
+                "fft".to_owned()
+            } else {
+                layer(i + 1)
+            };
+            for device in layer(i).split(' ') {
+                input.push_str(&format!("{}: {}\n", device, next));
+            }
+        }
+        input.push_str("fft: dac\ndac: out\n");
+
+        let devices = Day11::parse_input(input.as_bytes()).unwrap();
+        let count = devices.count_paths("svr", "out", "dac,fft", "").unwrap();
+        assert_eq!(count, 3u128.pow(20));
+        assert_ne!(count as f32 as u128, count);
+    }
+
+    #[test]
+    fn waypoints_in_any_order() {
+        let devices = Day11::parse_input(EXAMPLES[1].input.as_bytes()).unwrap();
+        assert_eq!(devices.count_paths("svr", "out", "", "").unwrap(), 8);
+        assert_eq!(devices.count_paths("svr", "out", "dac,fft", "").unwrap(), 2);
+        assert_eq!(
+            devices.count_paths("svr", "out", "fft, dac", "").unwrap(),
+            2
+        );
+        assert_eq!(devices.count_paths("svr", "out", "fft", "dac").unwrap(), 2);
+        assert_eq!(devices.count_paths("svr", "out", "fft,tty", "").unwrap(), 0);
+        let error = devices.count_paths("svr", "out", "xyz", "").unwrap_err();
+        assert_eq!(error.to_string(), "No device xyz");
     }
 
     #[test]
-    fn part_2_example() {
-        let day = Day11;
-        set_input_mode(PuzzleInputType::Example);
-        let result = day.run_part_2(day.get_exmaple_part_2().as_bytes()).unwrap();
-        assert_eq!(result, 2);
+    fn cycles_are_rejected() {
+        let input = b"svr: aaa\naaa: bbb out\nbbb: ccc\nccc: aaa";
+        let error = Day11::parse_input(input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(
+            error.message,
+            "devices form a cycle aaa -> bbb -> ccc -> aaa"
+        );
+
+        let input = b"svr: aaa ddd\naaa: bbb\nbbb: aaa out\nddd: ddd out";
+        let error = Day11::parse_input(input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(
+            error.message,
+            "devices form 2 cycles: aaa -> bbb -> aaa, ddd -> ddd"
+        );
+    }
+
+    #[test]
+    fn unreachable_devices_and_dangling_outputs() {
+        let devices = Day11::parse_input(EXAMPLES[0].input.as_bytes()).unwrap();
+        assert_eq!(
+            devices.warnings,
+            ["Devices unreachable from you: aaa, hhh, iii"]
+        );
+
+        let devices = Day11::parse_input(b"svr: aaa xyz\naaa: out\nbbb: out").unwrap();
+        assert_eq!(
+            devices.warnings,
+            [
+                "Outputs that are not devices: xyz",
+                "Devices unreachable from svr: bbb"
+            ]
+        );
     }
 }
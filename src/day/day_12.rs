@@ -2,7 +2,11 @@ use std::collections::HashMap;
 
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::{day::Solution, util::number::parse_u8_slice_to_i64};
+use crate::{
+    day::{Answer, Example, Part, RunContext, Solution},
+    error::ParseError,
+    util::{grid::Grid, number::parse_number},
+};
 
 use super::Day;
 
@@ -12,59 +16,55 @@ type PieceId = u8;
 
 #[derive(Debug)]
 struct PieceDefinition {
-    pattern: Vec<Vec<bool>>,
+    pattern: Grid<bool>,
     id: PieceId,
 }
 
 impl PieceDefinition {
-    fn from_lines(lines: &[&[u8]]) -> Self {
-        let mut iter = lines.iter();
-        let id = iter.next().unwrap()[0] - b'0';
-        let pattern = iter
-            .map(|line| line.iter().map(|&c| c == b'#').collect())
-            .collect();
-        PieceDefinition { pattern, id }
+    fn from_lines(input: &[u8], lines: &[&[u8]]) -> Result<Self, ParseError> {
+        let (header, rows) = match lines {
+            [header] => {
+                return Err(ParseError::at(
+                    input,
+                    header,
+                    "expected piece rows after the index",
+                ));
+            }
+            [header, rows @ ..] => (header, rows),
+            [] => return Err(ParseError::at(input, input, "expected a piece")),
+        };
+        let id = header
+            .strip_suffix(b":")
+            .ok_or_else(|| ParseError::at(input, header, "expected a piece index like `0:`"))?;
+        let id = parse_number(input, id)?;
+        let pattern = Grid::parse_rows(input, rows, "'#' or '.'", |c| match c {
+            b'#' => Some(true),
+            b'.' => Some(false),
+            _ => None,
+        })?;
+        Ok(PieceDefinition { pattern, id })
     }
 
     fn rotated(&self, right: u8) -> Self {
-        let mut new_pattern = self.pattern.clone();
+        let mut pattern = self.pattern.clone();
         for _ in 0..right {
-            let height = new_pattern.len();
-            let width = new_pattern[0].len();
-            let mut rotated = vec![vec![false; height]; width];
-            for r in 0..height {
-                for c in 0..width {
-                    rotated[c][height - 1 - r] = new_pattern[r][c];
-                }
-            }
-            new_pattern = rotated;
+            pattern = pattern.rotated();
         }
         PieceDefinition {
-            pattern: new_pattern,
+            pattern,
             id: self.id,
         }
     }
 
     fn flipped(&self) -> Self {
-        let height = self.pattern.len();
-        let width = self.pattern[0].len();
-        let mut flipped = vec![vec![false; width]; height];
-        for r in 0..height {
-            for c in 0..width {
-                flipped[r][width - 1 - c] = self.pattern[r][c];
-            }
-        }
         PieceDefinition {
-            pattern: flipped,
+            pattern: self.pattern.flipped(),
             id: self.id,
         }
     }
 
     fn weight(&self) -> usize {
-        self.pattern
-            .iter()
-            .map(|row| row.iter().filter(|&&cell| cell).count())
-            .sum()
+        self.pattern.find_all(&true).count()
     }
 }
 
@@ -76,28 +76,32 @@ struct BoardDefinition {
 }
 
 impl BoardDefinition {
-    fn from_line(line: &[u8]) -> Self {
-        let parts: Vec<&[u8]> = line.split(|&c| c == b':').collect();
-        let size_part = parts[0];
-        let pieces_part = parts[1];
+    fn from_line(input: &[u8], line: &[u8]) -> Result<Self, ParseError> {
+        let (size_part, pieces_part) = line
+            .iter()
+            .position(|&c| c == b':')
+            .map(|colon| (&line[..colon], &line[colon + 1..]))
+            .ok_or_else(|| ParseError::at(input, line, "expected a region like `4x4: 0 2`"))?;
 
-        let size_parts: Vec<&[u8]> = size_part.split(|&c| c == b'x').collect();
-        let width = parse_u8_slice_to_i64(size_parts[0]) as usize;
-        let height = parse_u8_slice_to_i64(size_parts[1]) as usize;
+        let x = size_part
+            .iter()
+            .position(|&c| c == b'x')
+            .ok_or_else(|| ParseError::at(input, size_part, "expected a size like `4x4`"))?;
+        let width = parse_number(input, &size_part[..x])?;
+        let height = parse_number(input, &size_part[x + 1..])?;
 
         let requested_pieces = pieces_part
             .split(|&c| c == b' ')
             .filter(|s| !s.is_empty())
-            .map(|s| parse_u8_slice_to_i64(s))
             .enumerate()
-            .map(|(id, count)| (id as PieceId, count))
-            .collect();
+            .map(|(id, s)| Ok((id as PieceId, parse_number(input, s)?)))
+            .collect::<Result<_, ParseError>>()?;
 
-        BoardDefinition {
+        Ok(BoardDefinition {
             width,
             height,
             requested_pieces,
-        }
+        })
     }
 
     fn area(&self) -> usize {
@@ -106,7 +110,9 @@ impl BoardDefinition {
 }
 
 impl Day12 {
-    fn parse_input(input: &[u8]) -> (Vec<PieceDefinition>, Vec<BoardDefinition>) {
+    fn parse_input(
+        input: &[u8],
+    ) -> Result<(Vec<PieceDefinition>, Vec<BoardDefinition>), ParseError> {
         let mut chunks = Vec::new();
         for line in input.split(|&c| c == b'\n') {
             if line.is_empty() {
@@ -118,24 +124,26 @@ impl Day12 {
                 chunks.push(vec![line]);
                 continue;
             }
-            if chunks.is_empty() {
-                chunks.push(Vec::new());
+            match chunks.last_mut() {
+                Some(chunk) => chunk.push(line),
+                None => chunks.push(vec![line]),
             }
-            chunks.last_mut().unwrap().push(line);
         }
 
         let mut piece_definitions = Vec::new();
         let mut board_definitions = Vec::new();
 
         for chunk in chunks.iter().filter(|c| !c.is_empty()) {
-            if chunk.len() == 1 {
-                board_definitions.push(BoardDefinition::from_line(chunk[0]));
+            if let [line] = chunk[..]
+                && line.contains(&b'x')
+            {
+                board_definitions.push(BoardDefinition::from_line(input, line)?);
             } else {
-                piece_definitions.push(PieceDefinition::from_lines(&chunk));
+                piece_definitions.push(PieceDefinition::from_lines(input, chunk)?);
             }
         }
 
-        (piece_definitions, board_definitions)
+        Ok((piece_definitions, board_definitions))
     }
 
     fn dfs_fit(board: &BoardDefinition, pieces: &Vec<PieceDefinition>) -> bool {
@@ -163,7 +171,7 @@ impl Day12 {
             pieces: &Vec<PieceDefinition>,
             placements: &mut Vec<PiecePlacement>,
             requested_pieces: &mut HashMap<PieceId, i64>,
-            current_board: &mut Vec<Vec<bool>>,
+            current_board: &mut Grid<bool>,
         ) -> bool {
             if requested_pieces.values().all(|&count| count == 0) {
                 return true;
@@ -177,11 +185,9 @@ impl Day12 {
                     piece.rotated(placement.rotation)
                 };
 
-                for (py, row) in transformed_piece.pattern.iter().enumerate() {
-                    for (px, &cell) in row.iter().enumerate() {
-                        if cell {
-                            current_board[placement.y + py][placement.x + px] = true;
-                        }
+                for ((px, py), &cell) in transformed_piece.pattern.iter() {
+                    if cell {
+                        current_board[(placement.x + px, placement.y + py)] = true;
                     }
                 }
             }
@@ -200,14 +206,12 @@ impl Day12 {
                             piece.rotated(rotation)
                         };
 
-                        for x in 0..=board.width - transformed_piece.pattern[0].len() {
-                            'outer: for y in 0..=board.height - transformed_piece.pattern.len() {
+                        for x in 0..=board.width - transformed_piece.pattern.width() {
+                            'outer: for y in 0..=board.height - transformed_piece.pattern.height() {
                                 // Check if the piece can be placed at (x, y)
-                                for (py, row) in transformed_piece.pattern.iter().enumerate() {
-                                    for (px, &cell) in row.iter().enumerate() {
-                                        if cell && current_board[y + py][x + px] {
-                                            continue 'outer;
-                                        }
+                                for ((px, py), &cell) in transformed_piece.pattern.iter() {
+                                    if cell && current_board[(x + px, y + py)] {
+                                        continue 'outer;
                                     }
                                 }
 
@@ -228,12 +232,10 @@ impl Day12 {
                                         piece.rotated(placement.rotation)
                                     };
 
-                                    for (py, row) in transformed_piece.pattern.iter().enumerate() {
-                                        for (px, &cell) in row.iter().enumerate() {
-                                            if cell {
-                                                current_board[placement.y + py][placement.x + px] =
-                                                    true;
-                                            }
+                                    for ((px, py), &cell) in transformed_piece.pattern.iter() {
+                                        if cell {
+                                            current_board[(placement.x + px, placement.y + py)] =
+                                                true;
                                         }
                                     }
                                 }
@@ -252,12 +254,10 @@ impl Day12 {
                                     } else {
                                         piece.rotated(placement.rotation)
                                     };
-                                    for (py, row) in transformed_piece.pattern.iter().enumerate() {
-                                        for (px, &cell) in row.iter().enumerate() {
-                                            if cell {
-                                                current_board[placement.y + py][placement.x + px] =
-                                                    false;
-                                            }
+                                    for ((px, py), &cell) in transformed_piece.pattern.iter() {
+                                        if cell {
+                                            current_board[(placement.x + px, placement.y + py)] =
+                                                false;
                                         }
                                     }
                                 }
@@ -274,7 +274,7 @@ impl Day12 {
         }
         let mut placements = Vec::new();
         let mut requested_pieces = board.requested_pieces.clone();
-        let mut current_board = vec![vec![false; board.width]; board.height];
+        let mut current_board = Grid::new(board.width, board.height, false);
         dfs(
             board,
             pieces,
@@ -290,20 +290,34 @@ impl Solution for Day12 {
         12
     }
 
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
-        let (piece_definitions, board_definitions) = Self::parse_input(input);
+    type Input<'a> = (Vec<PieceDefinition>, Vec<BoardDefinition>);
+
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn std::error::Error>> {
+        Ok(Self::parse_input(input)?)
+    }
+
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        _ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn std::error::Error>> {
+        let (piece_definitions, board_definitions) = input;
 
         let fits = board_definitions
             .par_iter()
-            .filter(|board| Self::dfs_fit(board, &piece_definitions))
+            .filter(|board| Self::dfs_fit(board, piece_definitions))
             .count();
 
-        Ok(fits as i64)
+        Ok(fits.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
     }
+}
 
-    fn get_example(&self) -> Option<&str> {
-        Some(
-            r#"0:
+examples! {
+    example: r#"0:
 ###
 ##.
 ##.
@@ -334,9 +348,9 @@ impl Solution for Day12 {
 ###
 
 4x4: 0 0 0 0 2 0
-12x5: 1 0 1 0 2 2"#,
-        )
-    }
+12x5: 1 0 1 0 2 2"# => {
+        Part::One => 2,
+    },
 }
 
 pub fn day() -> Box<dyn Day> {
@@ -347,49 +361,33 @@ pub fn day() -> Box<dyn Day> {
 mod test {
     use super::*;
 
+    fn piece(input: &[u8]) -> PieceDefinition {
+        let lines: Vec<&[u8]> = input.split(|&c| c == b'\n').collect();
+        PieceDefinition::from_lines(input, &lines).unwrap()
+    }
+
+    fn pattern(rows: &[u8]) -> Grid<bool> {
+        Grid::parse(rows, "'#' or '.'", |c| Some(c == b'#')).unwrap()
+    }
+
     #[test]
-    fn rotate() {
-        let piece = PieceDefinition::from_lines(&[b"0:", b"###", b"##.", b"##."]);
-        let rotated_once = piece.rotated(1);
-        assert_eq!(
-            rotated_once.pattern,
-            vec![
-                vec![true, true, true],
-                vec![true, true, true],
-                vec![false, false, true],
-            ]
-        );
-
-        let rotated_twice = piece.rotated(2);
-        assert_eq!(
-            rotated_twice.pattern,
-            vec![
-                vec![false, true, true],
-                vec![false, true, true],
-                vec![true, true, true],
-            ]
-        );
+    fn pieces_need_rows() {
+        let input = b"0:\n###\n\n1:\n\n4x4: 0 0";
+        let error = Day12::parse_input(input).err().unwrap();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.message, "expected piece rows after the index");
     }
 
     #[test]
-    fn flipped() {
-        let piece = PieceDefinition::from_lines(&[b"0:", b"###", b"##.", b"##."]);
-        let flipped = piece.flipped();
-        assert_eq!(
-            flipped.pattern,
-            vec![
-                vec![true, true, true],
-                vec![false, true, true],
-                vec![false, true, true],
-            ]
-        );
+    fn rotate() {
+        let piece = piece(b"0:\n###\n##.\n##.");
+        assert_eq!(piece.rotated(1).pattern, pattern(b"###\n###\n..#"));
+        assert_eq!(piece.rotated(2).pattern, pattern(b".##\n.##\n###"));
     }
 
     #[test]
-    fn part_1_example() {
-        let day = day();
-        let example_input = day.get_example().unwrap();
-        let result = day.run_part_1(example_input.as_bytes()).unwrap();
-        assert_eq!(result, 2);
+    fn flipped() {
+        let piece = piece(b"0:\n###\n##.\n##.");
+        assert_eq!(piece.flipped().pattern, pattern(b"###\n.##\n.##"));
     }
 }
@@ -0,0 +1,59 @@
+use std::error::Error;
+
+use crate::{
+    day::Part,
+    util::input::{PuzzleGetter, PuzzleInputType},
+};
+
+/// A named example input together with the answers it is known to produce.
+///
+/// Declared with [`examples!`](crate::examples), which also generates a test
+/// per example.
+pub struct Example {
+    pub name: &'static str,
+    pub input: &'static str,
+    pub answers: &'static [(Part, &'static str)],
+}
+
+impl Example {
+    pub fn applies_to(&self, part: Part) -> bool {
+        self.expected(part).is_some()
+    }
+
+    pub fn expected(&self, part: Part) -> Option<&'static str> {
+        self.answers
+            .iter()
+            .find(|(p, _)| *p == part)
+            .map(|&(_, answer)| answer)
+    }
+}
+
+impl PuzzleGetter for Example {
+    fn get_input(&self) -> Result<String, Box<dyn Error>> {
+        Ok(self.input.to_owned())
+    }
+
+    fn get_type(&self) -> PuzzleInputType {
+        PuzzleInputType::Example
+    }
+}
+
+/// Solves every part of the example `name` and asserts its expected answers.
+#[cfg(test)]
+pub fn check_example(day: &dyn crate::day::Day, name: &str) {
+    let example = day
+        .examples()
+        .iter()
+        .find(|e| e.name == name)
+        .expect("Example not declared");
+    for &(part, expected) in example.answers {
+        let result = day
+            .solve(
+                part,
+                example.input.as_bytes(),
+                &crate::day::RunContext::example(),
+            )
+            .unwrap();
+        assert_eq!(result.to_string(), expected, "part {}", part);
+    }
+}
@@ -1,16 +1,24 @@
 use std::{error::Error, fmt::Display};
 
 use crate::{
-    error::{ExampleInputNotAvailableError, PuzzleNotImplementedError},
+    error::{ParseError, PuzzleNotImplementedError},
     util::{
-        benchmark::Benchmarker,
-        input::{PuzzleGetter, PuzzleInputType},
+        benchmark::{Benchmarker, Phase},
+        input::PuzzleGetter,
     },
 };
 
-include!(concat!(env!("OUT_DIR"), "/days.rs"));
+mod answer;
+mod context;
+mod example;
+
+pub use answer::Answer;
+pub use context::{Logger, ParamSpec, Params, RunContext, StderrLogger, StdoutLogger};
+pub use example::Example;
+#[cfg(test)]
+pub use example::check_example;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Part {
     One,
     Two,
@@ -34,37 +42,14 @@ impl Part {
     }
 }
 
-static CURRENT_INPUT: std::sync::Mutex<Option<PuzzleInputType>> = std::sync::Mutex::new(None);
-pub fn set_input_mode(mode: PuzzleInputType) {
-    let mut guard = CURRENT_INPUT.lock().unwrap();
-    *guard = Some(mode);
-}
-
-#[allow(unused)]
-pub fn get_input_mode() -> PuzzleInputType {
-    let guard = CURRENT_INPUT.lock().unwrap();
-    guard.unwrap()
-}
-
-static IS_BENCHMARKING: std::sync::Mutex<bool> = std::sync::Mutex::new(false);
-pub fn set_benchmarking(is_benchmarking: bool) {
-    let mut guard = IS_BENCHMARKING.lock().unwrap();
-    *guard = is_benchmarking;
-}
-
-#[allow(unused)]
-pub fn is_benchmarking() -> bool {
-    let guard = IS_BENCHMARKING.lock().unwrap();
-    *guard
-}
-
 #[macro_export]
 macro_rules! example_println {
-    ($($arg:tt)*) => {
+    ($ctx:expr, $($arg:tt)*) => {
         #[cfg(debug_assertions)]
         {
-            if (!$crate::day::is_benchmarking() && $crate::day::get_input_mode() == $crate::util::input::PuzzleInputType::Example) {
-                println!($($arg)*);
+            let ctx: &$crate::day::RunContext = $ctx;
+            if (!ctx.is_benchmarking && ctx.input_type == $crate::util::input::PuzzleInputType::Example) {
+                ctx.log(&format!($($arg)*));
             }
         }
     };
@@ -72,63 +57,163 @@ macro_rules! example_println {
 
 #[macro_export]
 macro_rules! actual_println {
-    ($($arg:tt)*) => {
+    ($ctx:expr, $($arg:tt)*) => {
         #[cfg(debug_assertions)]
         {
-            if (!$crate::day::is_benchmarking() && $crate::day::get_input_mode() == $crate::util::input::PuzzleInputType::Actual) {
-                println!($($arg)*);
+            let ctx: &$crate::day::RunContext = $ctx;
+            if (!ctx.is_benchmarking && ctx.input_type == $crate::util::input::PuzzleInputType::Actual) {
+                ctx.log(&format!($($arg)*));
             }
         }
     };
 }
 
+/// Declares a day's examples as `EXAMPLES` and generates a test for each.
+///
+/// ```ignore
+/// examples! {
+///     example: "L68\nL30" => {
+///         Part::One => 3,
+///         Part::Two => 6,
+///     },
+/// }
+/// ```
+#[macro_export]
+macro_rules! examples {
+    ($($name:ident: $input:expr => { $($part:expr => $answer:literal),* $(,)? }),* $(,)?) => {
+        static EXAMPLES: &[$crate::day::Example] = &[
+            $(
+                $crate::day::Example {
+                    name: stringify!($name),
+                    input: $input,
+                    answers: &[$(($part, concat!($answer))),*],
+                },
+            )*
+        ];
+
+        #[cfg(test)]
+        mod examples {
+            $(
+                #[test]
+                fn $name() {
+                    $crate::day::check_example(&*super::day(), stringify!($name));
+                }
+            )*
+        }
+    };
+}
+
+// Included after the macros so the days can use them.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
 pub trait Solution {
+    /// The parsed puzzle input, shared by both parts.
+    type Input<'a>;
+
     fn number(&self) -> u8;
-    fn get_example(&self) -> Option<&str> {
-        None
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+    /// Parameters the parts read from their [`RunContext`].
+    fn params(&self) -> &'static [ParamSpec] {
+        &[]
     }
+    fn parse<'a>(&self, input: &'a [u8]) -> Result<Self::Input<'a>, Box<dyn Error>>;
     #[allow(unused_variables)]
-    fn run_part_1(&self, input: &[u8]) -> Result<i64, Box<dyn Error>> {
+    fn run_part_1(
+        &self,
+        input: &Self::Input<'_>,
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn Error>> {
         Err(PuzzleNotImplementedError.into())
     }
     #[allow(unused_variables)]
-    fn run_part_2(&self, input: &[u8]) -> Result<i64, Box<dyn Error>> {
+    fn run_part_2(
+        &self,
+        input: &Self::Input<'_>,
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn Error>> {
         Err(PuzzleNotImplementedError.into())
     }
 }
 
-impl<T: Solution> Day for T {}
+/// Object-safe view of a [`Solution`], used by the runner.
+pub trait Day: Sync {
+    fn number(&self) -> u8;
+    fn examples(&self) -> &'static [Example];
+    fn param_specs(&self) -> &'static [ParamSpec];
+    /// Parses `input` and solves `part` on it.
+    #[allow(unused)]
+    fn solve(&self, part: Part, input: &[u8], ctx: &RunContext) -> Result<Answer, Box<dyn Error>>;
+    /// Fetches the input, then parses and solves it, timing both phases.
+    fn run(
+        &self,
+        part: Part,
+        puzzle_getter: &dyn PuzzleGetter,
+        benchmarker: &mut dyn Benchmarker,
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn Error>>;
+}
+
+impl<T: Solution + Sync> Day for T {
+    fn number(&self) -> u8 {
+        Solution::number(self)
+    }
 
-pub trait Day: Solution + PuzzleGetter {}
+    fn examples(&self) -> &'static [Example] {
+        Solution::examples(self)
+    }
 
-impl dyn Day {
-    pub fn run(
+    fn param_specs(&self) -> &'static [ParamSpec] {
+        self.params()
+    }
+
+    fn solve(&self, part: Part, input: &[u8], ctx: &RunContext) -> Result<Answer, Box<dyn Error>> {
+        let ctx = &part_context(self, part, ctx);
+        let input = self.parse(input).map_err(|e| with_day(e, self.number()))?;
+        match part {
+            Part::One => self.run_part_1(&input, ctx),
+            Part::Two => self.run_part_2(&input, ctx),
+        }
+    }
+
+    fn run(
         &self,
         part: Part,
         puzzle_getter: &dyn PuzzleGetter,
         benchmarker: &mut dyn Benchmarker,
-    ) -> Result<i64, Box<dyn Error>> {
+        ctx: &RunContext,
+    ) -> Result<Answer, Box<dyn Error>> {
+        let ctx = &part_context(self, part, ctx);
         let input_string = puzzle_getter.get_input()?;
-        let input = input_string.as_bytes();
-        benchmarker.start_benchmark();
+        benchmarker.start_benchmark(Phase::Parse);
+        let input = self.parse(input_string.as_bytes());
+        benchmarker.end_benchmark(Phase::Parse);
+        let input = input.map_err(|e| with_day(e, self.number()))?;
+        benchmarker.start_benchmark(Phase::Solve);
         let result = match part {
-            Part::One => self.run_part_1(input),
-            Part::Two => self.run_part_2(input),
+            Part::One => self.run_part_1(&input, ctx),
+            Part::Two => self.run_part_2(&input, ctx),
         };
-        benchmarker.end_benchmark();
+        benchmarker.end_benchmark(Phase::Solve);
         result
     }
 }
 
-impl<T: Solution> PuzzleGetter for T {
-    fn get_input(&self) -> Result<String, Box<dyn Error>> {
-        match self.get_example() {
-            Some(example) => Ok(example.to_string()),
-            None => Err(ExampleInputNotAvailableError.into()),
+/// Records which day failed to parse its input, for [`ParseError`]s.
+fn with_day(error: Box<dyn Error>, day: u8) -> Box<dyn Error> {
+    match error.downcast::<ParseError>() {
+        Ok(mut error) => {
+            error.day = Some(day);
+            error
         }
+        Err(error) => error,
     }
+}
 
-    fn get_type(&self) -> PuzzleInputType {
-        PuzzleInputType::Example
-    }
+/// Narrows the runner's parameter overrides down to the resolved parameters
+/// of `part`.
+fn part_context<T: Solution>(day: &T, part: Part, ctx: &RunContext) -> RunContext {
+    let params = ctx.params.resolve(day.params(), part, ctx.input_type);
+    ctx.clone().with_params(params)
 }
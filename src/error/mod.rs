@@ -21,3 +21,95 @@ impl Display for ExampleInputNotAvailableError {
 }
 
 impl Error for ExampleInputNotAvailableError {}
+
+/// An invalid command line, with a message explaining what is wrong.
+#[derive(Debug, Clone)]
+pub struct UsageError(pub String);
+
+impl Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+/// Malformed puzzle input, pointing at the offending snippet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Filled in once the error leaves the day's parser.
+    pub day: Option<u8>,
+    /// 1-based position of the snippet in the input.
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about `snippet`, which must be a subslice of `input`.
+    pub fn at(input: &[u8], snippet: &[u8], message: impl Into<String>) -> Self {
+        let offset = (snippet.as_ptr() as usize)
+            .wrapping_sub(input.as_ptr() as usize)
+            .min(input.len());
+        let before = &input[..offset];
+        let line_start = before
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1);
+        Self {
+            day: None,
+            line: before.iter().filter(|&&c| c == b'\n').count() + 1,
+            column: offset - line_start + 1,
+            snippet: String::from_utf8_lossy(snippet).into_owned(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "Day {} input, line", day)?;
+        } else {
+            write!(f, "Line")?;
+        }
+        write!(
+            f,
+            " {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        match self.snippet.char_indices().nth(40) {
+            _ if self.snippet.is_empty() => Ok(()),
+            Some((end, _)) => write!(f, ": {:?}...", &self.snippet[..end]),
+            None => write!(f, ": {:?}", self.snippet),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_error_position() {
+        let input = b"12,34\n56,x7\n";
+        let error = ParseError::at(input, &input[9..11], "invalid digit 'x'");
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(
+            error.to_string(),
+            "Line 2, column 4: invalid digit 'x': \"x7\""
+        );
+
+        let error = ParseError {
+            day: Some(3),
+            ..ParseError::at(input, &input[12..], "expected a number")
+        };
+        assert_eq!(
+            error.to_string(),
+            "Day 3 input, line 3, column 1: expected a number"
+        );
+    }
+}
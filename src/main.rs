@@ -1,34 +1,70 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    day::Day,
-    error::{ExampleInputNotAvailableError, PuzzleNotImplementedError},
+    cli::{CacheCommand, Cli, Command, Options},
+    day::{Day, Logger, Part, RunContext, StderrLogger, StdoutLogger},
+    report::{Format, cprintln},
+    runner::{Output, Runner, TargetOutcome},
     util::{
-        benchmark::{Benchmarker, SimpleBenchmarker},
-        input::{DayCookiePuzzleInputGetter, PuzzleGetter, PuzzleInputType},
+        answers::AnswerStore,
+        benchmark::SimpleBenchmarker,
+        benchmark_history::{Baseline, BenchmarkHistory, current_commit, resolve_commit},
+        input::{
+            DayCookiePuzzleInputGetter, FilePuzzleGetter, PuzzleGetter, PuzzleInputType,
+            StdinPuzzleGetter, resolve_base_url,
+        },
+        input_cache::{InputCache, resolve_cache_dir},
+        submit::{DayCookieAnswerSubmitter, SubmissionLog, SubmissionVerdict},
     },
 };
 
+mod cli;
 mod day;
 mod error;
+mod report;
+mod runner;
 mod util;
 
-static BENCHMARK_FLAGS: &[&str] = &["-b", "--benchmark"];
+static YEAR: u16 = 2025;
 
 fn main() {
     dotenv::dotenv().ok();
-    let mut args: Vec<String> = std::env::args().skip(1).collect();
-    let flags = take_flags(&mut args);
-    let args = args;
-    let all_days = day::get_days();
-    let run_targets = {
-        let arg_targets = determine_run_targets(&args, &all_days);
-        if arg_targets.is_empty() {
-            all_days
-                .last()
-                .map(|d| d.as_run_targets())
-                .unwrap_or_default()
-        } else {
-            arg_targets
+    // Leaked so that timed-out runs can outlive the part they belong to.
+    let all_days: &'static [Box<dyn Day>] = day::get_days().leak();
+    let Cli { command, options } = match cli::parse(std::env::args().skip(1), all_days) {
+        Ok(cli) => cli,
+        Err(e) => {
+            cprintln!("\x1b[31m{}\x1b[0m", e);
+            println!("Run with --help for usage.");
+            std::process::exit(2);
+        }
+    };
+    let base_url = resolve_base_url(options.base_url.as_deref());
+    let cache = InputCache::new(resolve_cache_dir(options.cache_dir.as_deref()));
+
+    let run_targets = match command {
+        Command::Help => {
+            println!("{}", cli::HELP);
+            return;
         }
+        Command::Cache(command) => {
+            if !or_exit(run_cache_command(command, &cache)) {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::Submit { day, part } => match submit(day, part, all_days, base_url, cache) {
+            Ok(SubmissionVerdict::Correct) => return,
+            Ok(_) => std::process::exit(1),
+            Err(e) => {
+                cprintln!("\x1b[31mSubmission failed: \x1b[1m{}\x1b[0m", e);
+                std::process::exit(1);
+            }
+        },
+        Command::Run(run_targets) => run_targets,
     };
 
     if run_targets.is_empty() {
@@ -36,188 +72,211 @@ fn main() {
         return;
     }
 
-    for run in run_targets {
-        let day = all_days
-            .iter()
-            .find(|d| d.number() == run.day)
-            .expect("Can't find day");
-        let cookie_getter = DayCookiePuzzleInputGetter::new(2025, run.day);
-        let getter: &dyn PuzzleGetter = match run.input_type {
-            PuzzleInputType::Example => day.as_ref(),
-            PuzzleInputType::Actual => &cookie_getter,
-        };
-        let mut benchmarker = SimpleBenchmarker::new();
-
-        day::set_input_mode(run.input_type);
-        let is_benchmarking = has_flag(&flags, BENCHMARK_FLAGS);
-        if is_benchmarking {
-            day::set_benchmarking(true);
-        } else {
-            day::set_benchmarking(false);
+    let Options {
+        benchmark: is_benchmarking,
+        params,
+        budget,
+        compare,
+        max_regression,
+        format,
+        jobs,
+        timeout,
+        input,
+        ..
+    } = options;
+    let compare = or_exit(
+        compare
+            .map(|baseline| match baseline {
+                Baseline::Commit(revision) => resolve_commit(&revision).map(Baseline::Commit),
+                latest => Ok(latest),
+            })
+            .transpose(),
+    );
+    let mut history = is_benchmarking.then(|| or_exit(BenchmarkHistory::load()));
+    let mut reporter = format.reporter();
+    // Keep stdout parseable by sending the solutions' own output to stderr.
+    let logger: Arc<dyn Logger> = match format {
+        Format::Table => Arc::new(StdoutLogger),
+        _ => Arc::new(StderrLogger),
+    };
+    let runner = Runner {
+        days: all_days,
+        base_url,
+        cache,
+        params,
+        budget,
+        is_benchmarking,
+        compare,
+        max_regression,
+        commit: current_commit(),
+        timeout,
+        custom_input: input.map(|path| -> Arc<dyn PuzzleGetter + Send + Sync> {
+            match path.as_str() {
+                "-" => Arc::new(StdinPuzzleGetter::default()),
+                _ => Arc::new(FilePuzzleGetter::new(path)),
+            }
+        }),
+    };
+
+    let mut mismatches = 0;
+    let mut regressions = 0;
+    let mut summed = Duration::ZERO;
+    let mut report = |outcome: TargetOutcome| {
+        for output in outcome.outputs {
+            match output {
+                Output::Log(line) => logger.log(&line),
+                Output::Message(message) => reporter.message(&message),
+                Output::Record(record) => reporter.report(&record),
+            }
         }
-        let start = std::time::Instant::now();
-        let result = day.run(run.part, getter, &mut benchmarker);
-        while is_benchmarking
-            && start.elapsed().as_millis() < 2000
-            && benchmarker.n() < 1000
-            && result.is_ok()
-        {
-            day::set_benchmarking(true);
-            let _ = day.run(run.part, getter, &mut benchmarker);
-            day::set_benchmarking(false);
+        mismatches += outcome.mismatches;
+        regressions += outcome.regressions;
+        summed += outcome.elapsed;
+    };
+    let start = Instant::now();
+    if jobs > 1 {
+        runner.run_parallel(&run_targets, jobs, &mut report);
+    } else {
+        for &run in &run_targets {
+            report(runner.run_target(run, history.as_mut()));
         }
-        print_result(&run, result, &benchmarker, &flags);
     }
-}
-
-struct RunTarget {
-    day: u8,
-    part: day::Part,
-    input_type: PuzzleInputType,
-}
+    let wall_clock = start.elapsed();
+    if jobs > 1 {
+        reporter.message(&format!(
+            "\x1b[37mRan {} target(s) on {} jobs in {:.3}ms, {:.3}ms summed ({:.1}x)\x1b[0m",
+            run_targets.len(),
+            jobs,
+            wall_clock.as_secs_f64() * 1000.0,
+            summed.as_secs_f64() * 1000.0,
+            summed.as_secs_f64() / wall_clock.as_secs_f64()
+        ));
+    }
+    reporter.finish();
 
-impl dyn Day {
-    fn as_run_targets(&self) -> Vec<RunTarget> {
-        let mut targets = Vec::new();
-        for part in [day::Part::One, day::Part::Two] {
-            for input_type in [PuzzleInputType::Example, PuzzleInputType::Actual] {
-                targets.push(RunTarget {
-                    day: self.number(),
-                    part,
-                    input_type,
-                });
-            }
-        }
-        targets
+    if regressions > 0 {
+        reporter.message(&format!(
+            "\x1b[31;1m{} part(s) regressed beyond the allowed slowdown\x1b[0m",
+            regressions
+        ));
+    }
+    if mismatches > 0 {
+        reporter.message(&format!(
+            "\x1b[31;1m{} answer(s) did not match the recorded answers\x1b[0m",
+            mismatches
+        ));
+    }
+    if mismatches > 0 || regressions > 0 {
+        std::process::exit(1);
     }
 }
 
-fn determine_run_targets(args: &[String], available_days: &[Box<dyn Day>]) -> Vec<RunTarget> {
-    let mut targets = Vec::new();
-    let regex = regex::Regex::new(r"(\d+)(\.\d)?([ae])?").unwrap();
-    for arg in args {
-        if arg == "a" {
-            for day in available_days {
-                targets.extend({
-                    let mut all = day.as_run_targets();
-                    all.retain(|t| t.input_type == PuzzleInputType::Actual);
-                    all
-                });
-            }
-            continue;
-        }
-        if arg == "e" {
-            for day in available_days {
-                targets.extend({
-                    let mut all = day.as_run_targets();
-                    all.retain(|t| t.input_type == PuzzleInputType::Example);
-                    all
-                });
-            }
-            continue;
-        }
-        let result = if let Some(m) = regex.captures(&arg) {
-            m
-        } else {
-            continue;
-        };
-        let day = result.get(1).unwrap().as_str().parse::<u8>().unwrap();
-        let parts = if let Some(part_match) = result.get(2) {
-            match part_match.as_str() {
-                ".1" => vec![day::Part::One],
-                ".2" => vec![day::Part::Two],
-                _ => panic!("Invalid part specifier"),
-            }
-        } else {
-            vec![day::Part::One, day::Part::Two]
-        };
-        let input_types = if let Some(input_match) = result.get(3) {
-            match input_match.as_str() {
-                "a" => vec![PuzzleInputType::Actual],
-                "e" => vec![PuzzleInputType::Example],
-                _ => panic!("Invalid input type specifier"),
-            }
-        } else {
-            vec![PuzzleInputType::Example, PuzzleInputType::Actual]
-        };
-
-        for part in parts {
-            for input_type in &input_types {
-                targets.push(RunTarget {
-                    day,
-                    part,
-                    input_type: *input_type,
-                });
-            }
-        }
-    }
-    targets
+fn or_exit<T>(result: Result<T, Box<dyn std::error::Error>>) -> T {
+    result.unwrap_or_else(|e| {
+        cprintln!("\x1b[31m{}\x1b[0m", e);
+        std::process::exit(1);
+    })
 }
 
-fn print_result(
-    run: &RunTarget,
-    result: Result<i64, Box<dyn std::error::Error>>,
-    benchmarker: &dyn Benchmarker,
-    flags: &Vec<String>,
-) {
-    let identifier = format!(
-        "\x1b[37m[{:2}.{}{}]\x1b[0m",
-        run.day,
-        run.part.to_number(),
-        match run.input_type {
-            PuzzleInputType::Example => "e",
-            PuzzleInputType::Actual => "a",
+/// Lists, purges or verifies the cached inputs. Returns false if verification
+/// found broken entries.
+fn run_cache_command(
+    command: CacheCommand,
+    cache: &InputCache,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    match command {
+        CacheCommand::List => {
+            let entries = cache.entries()?;
+            println!(
+                "{} cached input(s) in {}",
+                entries.len(),
+                cache.dir().display()
+            );
+            for entry in entries {
+                println!("  {}", entry);
+            }
         }
-    );
-    match result {
-        Ok(value) => {
-            let mut message = String::new();
-            message.push_str(&identifier);
-            message.push_str(" \x1b[33;1m");
-            message.push_str(&if has_flag(flags, BENCHMARK_FLAGS) {
-                "<hidden>".to_owned()
-            } else {
-                value.to_string()
-            });
-            message.push_str(&format!(
-                "\x1b[0;37m in {:.3}ms",
-                benchmarker.elapsed_ms().unwrap()
-            ));
-            if benchmarker.n() > 1 {
-                message.push_str(&format!(" (n={})", benchmarker.n()));
+        CacheCommand::Purge(day) => {
+            let purged = cache.purge(YEAR, day)?;
+            for entry in &purged {
+                println!("Removed {}", entry.path.display());
             }
-            println!("{}", message);
+            println!("Purged {} cached input(s)", purged.len());
         }
-        Err(e) => {
-            match e {
-                _ if e.is::<ExampleInputNotAvailableError>() => {
-                    println!("{} \x1b[31m<no example available>\x1b[0m", identifier);
-                    return;
-                }
-                _ if e.is::<PuzzleNotImplementedError>() => {
-                    println!("{} \x1b[31m<not implemented>\x1b[0m", identifier);
-                    return;
+        CacheCommand::Verify => {
+            let entries = cache.entries()?;
+            let mut broken = 0;
+            for entry in &entries {
+                let problems = entry.problems();
+                if !problems.is_empty() {
+                    broken += 1;
+                    cprintln!(
+                        "\x1b[31m{}: {}\x1b[0m",
+                        entry.path.display(),
+                        problems.join(", ")
+                    );
                 }
-                _ => {}
             }
             println!(
-                "{} \x1b[31mError running puzzle: \x1b[1m{}\x1b[0m",
-                identifier, e
+                "{} of {} cached input(s) ok",
+                entries.len() - broken,
+                entries.len()
             );
+            return Ok(broken == 0);
         }
     }
+    Ok(true)
 }
 
-fn take_flags(args: &mut Vec<String>) -> Vec<String> {
-    let flags = args
+/// Runs a single part on the actual input and posts the result, unless the
+/// submission log already rules the answer out.
+fn submit(
+    day_number: u8,
+    part: Part,
+    all_days: &[Box<dyn Day>],
+    base_url: String,
+    cache: InputCache,
+) -> Result<SubmissionVerdict, Box<dyn std::error::Error>> {
+    let day = all_days
         .iter()
-        .filter(|arg| arg.starts_with("-"))
-        .cloned()
-        .collect();
-    args.retain(|arg| !arg.starts_with("-"));
-    flags
-}
+        .find(|d| d.number() == day_number)
+        .expect("Can't find day");
+
+    let mut answers = AnswerStore::load(YEAR, day_number)?;
+    if let Some(expected) = answers.expected(part, PuzzleInputType::Actual) {
+        return Err(format!(
+            "Day {} part {} is already solved with {}",
+            day_number, part, expected
+        )
+        .into());
+    }
 
-fn has_flag(flags: &[String], variants: &[&str]) -> bool {
-    flags.iter().any(|f| variants.contains(&f.as_str()))
+    let getter = DayCookiePuzzleInputGetter::new(YEAR, day_number, base_url.clone(), cache);
+    let ctx = RunContext::new(PuzzleInputType::Actual);
+    let answer = day
+        .run(part, &getter, &mut SimpleBenchmarker::new(), &ctx)?
+        .to_string();
+
+    let mut log = SubmissionLog::load(YEAR, day_number)?;
+    if let Some(reason) = log.rejection_reason(part, &answer) {
+        return Err(format!("Refusing to submit: {}", reason).into());
+    }
+
+    cprintln!(
+        "\x1b[37m[{:2}.{}a]\x1b[0m Submitting \x1b[33;1m{}\x1b[0m",
+        day_number,
+        part,
+        answer
+    );
+    let verdict =
+        DayCookieAnswerSubmitter::new(YEAR, day_number, base_url).submit(part, &answer)?;
+    log.append(part, &answer, verdict.clone())?;
+    if verdict == SubmissionVerdict::Correct {
+        answers.record(part, PuzzleInputType::Actual, &answer);
+        answers.save()?;
+        cprintln!("\x1b[32;1m{}\x1b[0m", verdict);
+    } else {
+        cprintln!("\x1b[31;1m{}\x1b[0m", verdict);
+    }
+    Ok(verdict)
 }
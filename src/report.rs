@@ -0,0 +1,555 @@
+use std::{error::Error, io::IsTerminal, str::FromStr, sync::OnceLock, time::Duration};
+
+use crate::{
+    day::{Answer, Part},
+    error::{ExampleInputNotAvailableError, PuzzleNotImplementedError},
+    util::{
+        answers::Verdict, benchmark::Stats, benchmark_history::Comparison, input::PuzzleInputType,
+    },
+};
+
+/// Whether ANSI colors are written, i.e. stdout is a terminal and `NO_COLOR`
+/// is not set.
+pub fn use_colors() -> bool {
+    static USE_COLORS: OnceLock<bool> = OnceLock::new();
+    *USE_COLORS
+        .get_or_init(|| std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none())
+}
+
+/// Removes ANSI escape sequences from `text` unless colors are enabled.
+pub fn paint(text: &str) -> String {
+    if use_colors() {
+        text.to_owned()
+    } else {
+        strip_ansi(text)
+    }
+}
+
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+/// `println!` for colored output, dropping the colors when they are disabled.
+macro_rules! cprintln {
+    ($($arg:tt)*) => {
+        println!("{}", $crate::report::paint(&format!($($arg)*)))
+    };
+}
+
+pub(crate) use cprintln;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "Unknown format {:?}, expected json, csv or table",
+                s
+            )),
+        }
+    }
+}
+
+impl Format {
+    pub fn reporter(self) -> Box<dyn Reporter> {
+        match self {
+            Format::Table => Box::new(TableReporter),
+            Format::Json => Box::new(JsonReporter { records: vec![] }),
+            Format::Csv => Box::new(CsvReporter { has_header: false }),
+        }
+    }
+}
+
+pub enum Status {
+    Ok(Answer),
+    NotImplemented,
+    NoExample,
+    Error(String),
+    /// The solution panicked, with the panic message.
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl Status {
+    pub fn from_result(result: Result<Answer, Box<dyn Error>>) -> Self {
+        match result {
+            Ok(answer) => Status::Ok(answer),
+            Err(e) if e.is::<PuzzleNotImplementedError>() => Status::NotImplemented,
+            Err(e) if e.is::<ExampleInputNotAvailableError>() => Status::NoExample,
+            Err(e) => Status::Error(e.to_string()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Ok(_) => "ok",
+            Status::NotImplemented => "not_implemented",
+            Status::NoExample => "no_example",
+            Status::Error(_) => "error",
+            Status::Panicked(_) => "panicked",
+            Status::TimedOut(_) => "timed_out",
+        }
+    }
+
+    fn answer(&self) -> Option<String> {
+        match self {
+            Status::Ok(answer) => Some(answer.to_string()),
+            _ => None,
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        match self {
+            Status::Error(message) | Status::Panicked(message) => Some(message.clone()),
+            Status::TimedOut(timeout) => Some(format!("timed out after {}ms", timeout.as_millis())),
+            _ => None,
+        }
+    }
+}
+
+pub enum BaselineStatus {
+    NotCompared,
+    Missing,
+    Compared {
+        commit: String,
+        comparison: Comparison,
+        is_regression: bool,
+    },
+}
+
+/// The outcome of running a part on one input.
+pub struct RunRecord<'a> {
+    pub day: u8,
+    pub part: Part,
+    pub input_type: PuzzleInputType,
+    /// Name of the example, for example inputs.
+    pub example: Option<&'a str>,
+    pub status: Status,
+    pub verdict: Verdict,
+    pub solve: Option<Stats>,
+    pub parse: Option<Stats>,
+    /// Hides the answer in the table, e.g. while benchmarking.
+    pub is_hidden: bool,
+    pub baseline: BaselineStatus,
+}
+
+impl RunRecord<'_> {
+    fn input_name(&self) -> &'static str {
+        match self.input_type {
+            PuzzleInputType::Example => "example",
+            PuzzleInputType::Actual => "actual",
+            PuzzleInputType::Custom => "custom",
+        }
+    }
+
+    fn verdict_name(&self) -> &'static str {
+        match self.verdict {
+            Verdict::Correct => "correct",
+            Verdict::Wrong { .. } => "wrong",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn expected(&self) -> Option<&str> {
+        match &self.verdict {
+            Verdict::Wrong { expected } => Some(expected),
+            _ => None,
+        }
+    }
+}
+
+pub trait Reporter {
+    fn report(&mut self, record: &RunRecord);
+    /// Reports a message that is not part of a record, such as a summary.
+    fn message(&mut self, message: &str);
+    fn finish(&mut self) {}
+}
+
+pub struct TableReporter;
+
+impl Reporter for TableReporter {
+    fn report(&mut self, record: &RunRecord) {
+        let identifier = format!(
+            "\x1b[37m[{:2}.{}{}{}]\x1b[0m",
+            record.day,
+            record.part.to_number(),
+            match record.input_type {
+                PuzzleInputType::Example => "e",
+                PuzzleInputType::Actual => "a",
+                PuzzleInputType::Custom => "c",
+            },
+            // The single example of most days is simply called `example`.
+            record
+                .example
+                .filter(|&name| name != "example")
+                .map(|name| format!(" {}", name))
+                .unwrap_or_default()
+        );
+        let value = match &record.status {
+            Status::Ok(value) => value,
+            Status::NoExample => {
+                cprintln!("{} \x1b[31m<no example available>\x1b[0m", identifier);
+                return;
+            }
+            Status::NotImplemented => {
+                cprintln!("{} \x1b[31m<not implemented>\x1b[0m", identifier);
+                return;
+            }
+            Status::Error(e) => {
+                cprintln!(
+                    "{} \x1b[31mError running puzzle: \x1b[1m{}\x1b[0m",
+                    identifier,
+                    e
+                );
+                return;
+            }
+            Status::Panicked(message) => {
+                cprintln!("{} \x1b[31mPanicked: \x1b[1m{}\x1b[0m", identifier, message);
+                return;
+            }
+            Status::TimedOut(timeout) => {
+                cprintln!(
+                    "{} \x1b[31mTimed out after {}ms\x1b[0m",
+                    identifier,
+                    timeout.as_millis()
+                );
+                return;
+            }
+        };
+
+        let is_grid = matches!(value, Answer::Grid(_));
+        let mut message = String::new();
+        message.push_str(&identifier);
+        message.push_str(" \x1b[33;1m");
+        message.push_str(&if record.is_hidden {
+            "<hidden>".to_owned()
+        } else if is_grid {
+            "<grid>".to_owned()
+        } else {
+            value.to_string()
+        });
+        message.push_str(match record.verdict {
+            Verdict::Correct => " \x1b[0;32m✓",
+            Verdict::Wrong { .. } => " \x1b[0;31m✗",
+            Verdict::Unknown => " \x1b[0;37m?",
+        });
+        if let (Some(solve), Some(parse)) = (&record.solve, &record.parse) {
+            message.push_str(&format!(
+                "\x1b[0;37m in {:.3}ms (parse {:.3}ms)",
+                solve.mean, parse.mean
+            ));
+        }
+        if let Some(stats) = record.solve.as_ref().filter(|s| s.n > 1) {
+            message.push_str(&format!(
+                " (n={}, min {:.3}ms, median {:.3}ms, p95 {:.3}ms, p99 {:.3}ms, max {:.3}ms, σ {:.3}ms, {} outliers)",
+                stats.n,
+                stats.min,
+                stats.median,
+                stats.p95,
+                stats.p99,
+                stats.max,
+                stats.stddev,
+                stats.outliers
+            ));
+        }
+        if let Some(expected) = record.expected() {
+            message.push_str(&format!(" \x1b[31mexpected {}\x1b[0m", expected));
+        }
+        cprintln!("{}", message);
+        if is_grid && !record.is_hidden {
+            for row in value.to_string().lines() {
+                cprintln!("        \x1b[33;1m{}\x1b[0m", row);
+            }
+        }
+
+        match &record.baseline {
+            BaselineStatus::NotCompared => {}
+            BaselineStatus::Missing => cprintln!("        \x1b[37mno baseline\x1b[0m"),
+            BaselineStatus::Compared {
+                commit,
+                comparison,
+                is_regression,
+            } => {
+                let color = if *is_regression {
+                    "31;1"
+                } else if !comparison.is_significant {
+                    "37"
+                } else if comparison.change_percent > 0.0 {
+                    "33"
+                } else {
+                    "32"
+                };
+                let change = if !comparison.is_significant {
+                    "no significant change".to_owned()
+                } else if comparison.change_percent > 0.0 {
+                    format!("{:.1}% slower", comparison.change_percent)
+                } else {
+                    format!("{:.1}% faster", -comparison.change_percent)
+                };
+                cprintln!(
+                    "        \x1b[37mvs {}: {:.3}ms → {:.3}ms, \x1b[{}m{}\x1b[0m",
+                    commit,
+                    comparison.baseline_mean,
+                    comparison.mean,
+                    color,
+                    change
+                );
+            }
+        }
+    }
+
+    fn message(&mut self, message: &str) {
+        cprintln!("{}", message);
+    }
+}
+
+/// Collects all records and prints them as a single JSON array.
+pub struct JsonReporter {
+    records: Vec<String>,
+}
+
+impl Reporter for JsonReporter {
+    fn report(&mut self, record: &RunRecord) {
+        let stats = |stats: &Option<Stats>| match stats {
+            Some(s) => format!(
+                "{{\"n\": {}, \"mean_ms\": {}, \"min_ms\": {}, \"median_ms\": {}, \"p95_ms\": {}, \"p99_ms\": {}, \"max_ms\": {}, \"stddev_ms\": {}, \"outliers\": {}}}",
+                s.n,
+                json_number(s.mean),
+                json_number(s.min),
+                json_number(s.median),
+                json_number(s.p95),
+                json_number(s.p99),
+                json_number(s.max),
+                json_number(s.stddev),
+                s.outliers
+            ),
+            None => "null".to_owned(),
+        };
+        let baseline = match &record.baseline {
+            BaselineStatus::Compared {
+                commit,
+                comparison,
+                is_regression,
+            } => format!(
+                "{{\"commit\": {}, \"mean_ms\": {}, \"change_percent\": {}, \"significant\": {}, \"regression\": {}}}",
+                json_string(commit),
+                json_number(comparison.baseline_mean),
+                json_number(comparison.change_percent),
+                comparison.is_significant,
+                is_regression
+            ),
+            _ => "null".to_owned(),
+        };
+        self.records.push(format!(
+            "{{\"day\": {}, \"part\": {}, \"input\": {}, \"example\": {}, \"status\": {}, \"error\": {}, \"answer\": {}, \"verdict\": {}, \"expected\": {}, \"solve\": {}, \"parse\": {}, \"baseline\": {}}}",
+            record.day,
+            record.part,
+            json_string(record.input_name()),
+            json_option(record.example),
+            json_string(record.status.name()),
+            json_option(record.status.error().as_deref()),
+            json_option(record.status.answer().as_deref()),
+            json_string(record.verdict_name()),
+            json_option(record.expected()),
+            stats(&record.solve),
+            stats(&record.parse),
+            baseline
+        ));
+    }
+
+    fn message(&mut self, message: &str) {
+        eprintln!("{}", strip_ansi(message));
+    }
+
+    fn finish(&mut self) {
+        if self.records.is_empty() {
+            println!("[]");
+        } else {
+            println!("[\n  {}\n]", self.records.join(",\n  "));
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_option(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_owned(), json_string)
+}
+
+/// JSON has no NaN or infinities, so those become `null`.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_owned()
+    }
+}
+
+/// Prints one CSV row per record, after a header row.
+pub struct CsvReporter {
+    has_header: bool,
+}
+
+impl Reporter for CsvReporter {
+    fn report(&mut self, record: &RunRecord) {
+        if !self.has_header {
+            println!(
+                "day,part,input,example,status,error,answer,verdict,expected,n,mean_ms,min_ms,median_ms,p95_ms,p99_ms,max_ms,stddev_ms,outliers,parse_mean_ms,baseline_commit,baseline_mean_ms,change_percent,significant,regression"
+            );
+            self.has_header = true;
+        }
+        let solve = record.solve.as_ref();
+        let stat = |f: fn(&Stats) -> String| solve.map(f).unwrap_or_default();
+        let (commit, baseline_mean, change_percent, significant, regression) =
+            match &record.baseline {
+                BaselineStatus::Compared {
+                    commit,
+                    comparison,
+                    is_regression,
+                } => (
+                    commit.clone(),
+                    comparison.baseline_mean.to_string(),
+                    comparison.change_percent.to_string(),
+                    comparison.is_significant.to_string(),
+                    is_regression.to_string(),
+                ),
+                _ => Default::default(),
+            };
+        let fields = [
+            record.day.to_string(),
+            record.part.to_string(),
+            record.input_name().to_owned(),
+            record.example.unwrap_or_default().to_owned(),
+            record.status.name().to_owned(),
+            record.status.error().unwrap_or_default(),
+            record.status.answer().unwrap_or_default(),
+            record.verdict_name().to_owned(),
+            record.expected().unwrap_or_default().to_owned(),
+            stat(|s| s.n.to_string()),
+            stat(|s| s.mean.to_string()),
+            stat(|s| s.min.to_string()),
+            stat(|s| s.median.to_string()),
+            stat(|s| s.p95.to_string()),
+            stat(|s| s.p99.to_string()),
+            stat(|s| s.max.to_string()),
+            stat(|s| s.stddev.to_string()),
+            stat(|s| s.outliers.to_string()),
+            record
+                .parse
+                .as_ref()
+                .map(|s| s.mean.to_string())
+                .unwrap_or_default(),
+            commit,
+            baseline_mean,
+            change_percent,
+            significant,
+            regression,
+        ];
+        println!(
+            "{}",
+            fields
+                .iter()
+                .map(|f| csv_field(f))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
+
+    fn message(&mut self, message: &str) {
+        eprintln!("{}", strip_ansi(message));
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(json_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+        assert_eq!(json_option(None), "null");
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,\"b\""), r#""a,""b""""#);
+    }
+
+    #[test]
+    fn non_finite_json_stats() {
+        let stats = Stats {
+            n: 1,
+            mean: 1.5,
+            min: 1.5,
+            max: f64::INFINITY,
+            median: 1.5,
+            p95: 1.5,
+            p99: 1.5,
+            stddev: f64::NAN,
+            outliers: 0,
+        };
+        let mut reporter = JsonReporter { records: vec![] };
+        reporter.report(&RunRecord {
+            day: 1,
+            part: Part::One,
+            input_type: PuzzleInputType::Actual,
+            example: None,
+            status: Status::Ok(Answer::from(1)),
+            verdict: Verdict::Unknown,
+            solve: Some(stats),
+            parse: None,
+            is_hidden: true,
+            baseline: BaselineStatus::NotCompared,
+        });
+        let record = &reporter.records[0];
+        assert!(record.contains(r#""mean_ms": 1.5"#));
+        assert!(record.contains(r#""max_ms": null"#));
+        assert!(record.contains(r#""stddev_ms": null"#));
+        assert!(!record.contains("NaN") && !record.contains("inf"));
+    }
+}
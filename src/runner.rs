@@ -0,0 +1,522 @@
+use std::{
+    any::Any,
+    error::Error,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    YEAR,
+    cli::RunTarget,
+    day::{Answer, Day, Example, Logger, Params, RunContext},
+    report::{BaselineStatus, RunRecord, Status},
+    util::{
+        answers::{AnswerStore, Verdict},
+        benchmark::{BenchmarkBudget, Benchmarker, Phase, SimpleBenchmarker},
+        benchmark_history::{Baseline, BenchmarkHistory, BenchmarkRecord, Comparison},
+        input::{DayCookiePuzzleInputGetter, PuzzleGetter, PuzzleInputType},
+        input_cache::InputCache,
+    },
+};
+
+/// Something to print for a target, in the order it happened.
+pub enum Output {
+    /// A line logged by the solution.
+    Log(String),
+    Message(String),
+    Record(Box<RunRecord<'static>>),
+}
+
+pub struct TargetOutcome {
+    pub outputs: Vec<Output>,
+    pub mismatches: usize,
+    pub regressions: usize,
+    /// Time spent on the target, including fetching its input.
+    pub elapsed: Duration,
+}
+
+/// Collects the lines a solution logs, so that runs on other threads do not
+/// interleave with them.
+#[derive(Default)]
+struct BufferedLogger {
+    lines: Mutex<Vec<String>>,
+}
+
+impl BufferedLogger {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.lines.lock().unwrap())
+    }
+}
+
+impl Logger for BufferedLogger {
+    fn log(&self, message: &str) {
+        self.lines.lock().unwrap().push(message.to_owned());
+    }
+}
+
+pub struct Runner {
+    pub days: &'static [Box<dyn Day>],
+    pub base_url: String,
+    pub cache: InputCache,
+    pub params: Params,
+    pub budget: BenchmarkBudget,
+    pub is_benchmarking: bool,
+    pub compare: Option<Baseline>,
+    pub max_regression: f64,
+    /// Commit that benchmarks are recorded under.
+    pub commit: String,
+    /// Time after which a part is given up on.
+    pub timeout: Option<Duration>,
+    /// Input for targets of [`PuzzleInputType::Custom`].
+    pub custom_input: Option<Arc<dyn PuzzleGetter + Send + Sync>>,
+}
+
+impl Runner {
+    /// Runs the part on every input the target selects. Benchmarks are
+    /// compared against and recorded to `history`, if given.
+    pub fn run_target(
+        &self,
+        run: RunTarget,
+        mut history: Option<&mut BenchmarkHistory>,
+    ) -> TargetOutcome {
+        let start = Instant::now();
+        let mut outcome = TargetOutcome {
+            outputs: vec![],
+            mismatches: 0,
+            regressions: 0,
+            elapsed: Duration::ZERO,
+        };
+        let day = self
+            .days
+            .iter()
+            .find(|d| d.number() == run.day)
+            .expect("Can't find day");
+        let inputs: Vec<Source> = match run.input_type {
+            PuzzleInputType::Example => day
+                .examples()
+                .iter()
+                .filter(|e| e.applies_to(run.part))
+                .map(Source::Example)
+                .collect(),
+            PuzzleInputType::Actual => {
+                vec![Source::Actual(Arc::new(DayCookiePuzzleInputGetter::new(
+                    YEAR,
+                    run.day,
+                    self.base_url.clone(),
+                    self.cache.clone(),
+                )))]
+            }
+            PuzzleInputType::Custom => vec![Source::Custom(
+                self.custom_input
+                    .clone()
+                    .expect("Custom targets need a custom input"),
+            )],
+        };
+        if inputs.is_empty() {
+            outcome.outputs.push(Output::Record(Box::new(RunRecord {
+                day: run.day,
+                part: run.part,
+                input_type: run.input_type,
+                example: None,
+                status: Status::NoExample,
+                verdict: Verdict::Unknown,
+                solve: None,
+                parse: None,
+                is_hidden: self.is_benchmarking,
+                baseline: BaselineStatus::NotCompared,
+            })));
+        }
+
+        let logger = Arc::new(BufferedLogger::default());
+        for source in inputs {
+            let example = match source {
+                Source::Example(example) => Some(example),
+                _ => None,
+            };
+            let mut ctx = RunContext::new(run.input_type)
+                .with_params(self.params.clone())
+                .with_logger(logger.clone());
+            ctx.is_benchmarking = self.is_benchmarking;
+            let (status, benchmarker) = self.run_isolated(day.as_ref(), run, &source, &ctx);
+            outcome
+                .outputs
+                .extend(logger.take().into_iter().map(Output::Log));
+            let is_ok = matches!(status, Status::Ok(_));
+            let is_overridden = day
+                .param_specs()
+                .iter()
+                .any(|spec| spec.applies_to(run.part) && self.params.get(spec.name).is_some());
+            let verdict = match (&status, example) {
+                // Recorded answers only hold for the default parameters.
+                _ if is_overridden => Verdict::Unknown,
+                (Status::Ok(value), Some(example)) => match example.expected(run.part) {
+                    Some(expected) if expected == value.to_string() => Verdict::Correct,
+                    Some(expected) => Verdict::Wrong {
+                        expected: expected.to_owned(),
+                    },
+                    None => Verdict::Unknown,
+                },
+                (Status::Ok(value), None) if run.input_type == PuzzleInputType::Actual => {
+                    match AnswerStore::load(YEAR, run.day) {
+                        Ok(answers) => answers.verify(run.part, run.input_type, &value.to_string()),
+                        Err(e) => {
+                            outcome.outputs.push(Output::Message(format!(
+                                "\x1b[31mFailed to load answers: \x1b[1m{}\x1b[0m",
+                                e
+                            )));
+                            Verdict::Unknown
+                        }
+                    }
+                }
+                _ => Verdict::Unknown,
+            };
+            if let Verdict::Wrong { .. } = verdict {
+                outcome.mismatches += 1;
+            }
+
+            let solve = benchmarker.stats(Phase::Solve);
+            let input = example.map_or("actual", |e| e.name);
+            let mut baseline = BaselineStatus::NotCompared;
+            // Custom inputs change between runs, so their timings are not comparable.
+            let is_recorded = is_ok && run.input_type != PuzzleInputType::Custom;
+            if let (Some(history), Some(stats), true) = (&mut history, &solve, is_recorded) {
+                if let Some(compare_with) = &self.compare {
+                    baseline = match history.baseline(run.day, run.part, input, compare_with) {
+                        Some(record) => {
+                            let comparison = Comparison::new(&record.stats, stats);
+                            let is_regression = comparison.is_regression(self.max_regression);
+                            if is_regression {
+                                outcome.regressions += 1;
+                            }
+                            BaselineStatus::Compared {
+                                commit: record.commit.clone(),
+                                comparison,
+                                is_regression,
+                            }
+                        }
+                        None => BaselineStatus::Missing,
+                    };
+                }
+                let record = BenchmarkRecord {
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                    commit: self.commit.clone(),
+                    day: run.day,
+                    part: run.part.to_number(),
+                    input: input.to_owned(),
+                    stats: stats.clone(),
+                };
+                if let Err(e) = history.append(record) {
+                    outcome.outputs.push(Output::Message(format!(
+                        "\x1b[31mFailed to record benchmark: \x1b[1m{}\x1b[0m",
+                        e
+                    )));
+                }
+            }
+
+            outcome.outputs.push(Output::Record(Box::new(RunRecord {
+                day: run.day,
+                part: run.part,
+                input_type: run.input_type,
+                example: example.map(|e| e.name),
+                status,
+                verdict,
+                solve,
+                parse: benchmarker.stats(Phase::Parse),
+                is_hidden: self.is_benchmarking,
+                baseline,
+            })));
+        }
+        outcome.elapsed = start.elapsed();
+        outcome
+    }
+
+    /// Runs the part once, and when benchmarking repeats it within the budget.
+    /// A panic in any of the runs is reported as [`Status::Panicked`]. With a
+    /// timeout the runs happen on their own thread, which is abandoned (and
+    /// keeps running until the process exits) once a single run takes longer
+    /// than the timeout.
+    fn run_isolated(
+        &self,
+        day: &'static dyn Day,
+        run: RunTarget,
+        source: &Source,
+        ctx: &RunContext,
+    ) -> (Status, SimpleBenchmarker) {
+        let budget = self.is_benchmarking.then_some(self.budget);
+        let Some(timeout) = self.timeout else {
+            let mut benchmarker = SimpleBenchmarker::new();
+            let status = run_repeatedly(day, run, source, &mut benchmarker, ctx, budget, || {});
+            return (status, benchmarker);
+        };
+
+        // The thread sends `None` after every run, so that each run gets the
+        // whole timeout rather than the benchmark as a whole.
+        let (sender, receiver) = mpsc::channel();
+        let source = source.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let mut benchmarker = SimpleBenchmarker::new();
+            let status = run_repeatedly(day, run, &source, &mut benchmarker, &ctx, budget, || {
+                let _ = sender.send(None);
+            });
+            let _ = sender.send(Some((status, benchmarker)));
+        });
+        loop {
+            match receiver.recv_timeout(timeout) {
+                Ok(None) => {}
+                Ok(Some(result)) => return result,
+                Err(_) => return (Status::TimedOut(timeout), SimpleBenchmarker::new()),
+            }
+        }
+    }
+
+    /// Runs `targets` on `jobs` threads and hands the outcomes to `report` in
+    /// target order, as soon as all earlier targets are done.
+    ///
+    /// The days' own parallelism stays on the global rayon pool, so the jobs
+    /// are plain threads rather than rayon tasks.
+    pub fn run_parallel(
+        &self,
+        targets: &[RunTarget],
+        jobs: usize,
+        mut report: impl FnMut(TargetOutcome),
+    ) {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..jobs.min(targets.len()) {
+                let sender = sender.clone();
+                let next = &next;
+                scope.spawn(move || {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&run) = targets.get(index) else {
+                            break;
+                        };
+                        if sender.send((index, self.run_target(run, None))).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut pending: Vec<Option<TargetOutcome>> =
+                (0..targets.len()).map(|_| None).collect();
+            let mut reported = 0;
+            for (index, outcome) in receiver {
+                pending[index] = Some(outcome);
+                while let Some(outcome) = pending.get_mut(reported).and_then(Option::take) {
+                    report(outcome);
+                    reported += 1;
+                }
+            }
+        });
+    }
+}
+
+/// Where the input of a single run comes from.
+#[derive(Clone)]
+enum Source {
+    Example(&'static Example),
+    /// Built once per target, so that benchmark runs reuse its input.
+    Actual(Arc<DayCookiePuzzleInputGetter>),
+    Custom(Arc<dyn PuzzleGetter + Send + Sync>),
+}
+
+fn run_part(
+    day: &dyn Day,
+    run: RunTarget,
+    source: &Source,
+    benchmarker: &mut dyn Benchmarker,
+    ctx: &RunContext,
+) -> Result<Answer, Box<dyn Error>> {
+    match source {
+        Source::Example(example) => day.run(run.part, *example, benchmarker, ctx),
+        Source::Actual(getter) => day.run(run.part, getter.as_ref(), benchmarker, ctx),
+        Source::Custom(getter) => day.run(run.part, getter.as_ref(), benchmarker, ctx),
+    }
+}
+
+/// Runs the part once and, given a budget and a successful first run, keeps
+/// repeating it until the budget is spent or a run fails. `finished` is called
+/// after every run. The status is that of the first failing run, if any.
+fn run_repeatedly(
+    day: &dyn Day,
+    run: RunTarget,
+    source: &Source,
+    benchmarker: &mut dyn Benchmarker,
+    ctx: &RunContext,
+    budget: Option<BenchmarkBudget>,
+    mut finished: impl FnMut(),
+) -> Status {
+    let status = catch_panic(|| run_part(day, run, source, benchmarker, ctx));
+    finished();
+    let (Some(budget), Status::Ok(_)) = (budget, &status) else {
+        return status;
+    };
+    let mut failure = None;
+    budget.repeat(benchmarker, |benchmarker| {
+        let status = catch_panic(|| run_part(day, run, source, benchmarker, ctx));
+        finished();
+        match status {
+            Status::Ok(_) => true,
+            status => {
+                failure = Some(status);
+                false
+            }
+        }
+    });
+    failure.unwrap_or(status)
+}
+
+fn catch_panic(run: impl FnOnce() -> Result<Answer, Box<dyn Error>>) -> Status {
+    match panic::catch_unwind(AssertUnwindSafe(run)) {
+        Ok(result) => Status::from_result(result),
+        Err(payload) => Status::Panicked(panic_message(payload.as_ref()).to_owned()),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "<non-string panic payload>"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    use crate::day::{Part, Solution};
+
+    struct Faulty;
+
+    static EXAMPLE: Example = Example {
+        name: "example",
+        input: "",
+        answers: &[(Part::One, "0"), (Part::Two, "0")],
+    };
+
+    impl Solution for Faulty {
+        type Input<'a> = ();
+
+        fn number(&self) -> u8 {
+            1
+        }
+
+        fn parse(&self, _input: &[u8]) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn run_part_1(&self, _input: &(), _ctx: &RunContext) -> Result<Answer, Box<dyn Error>> {
+            panic!("Unknown operation");
+        }
+
+        fn run_part_2(&self, _input: &(), _ctx: &RunContext) -> Result<Answer, Box<dyn Error>> {
+            std::thread::sleep(Duration::from_secs(5));
+            Ok(0.into())
+        }
+    }
+
+    /// Succeeds on its first run, so that only benchmark repetitions fail.
+    struct Flaky {
+        runs: AtomicUsize,
+    }
+
+    impl Solution for Flaky {
+        type Input<'a> = ();
+
+        fn number(&self) -> u8 {
+            1
+        }
+
+        fn parse(&self, _input: &[u8]) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn run_part_1(&self, _input: &(), _ctx: &RunContext) -> Result<Answer, Box<dyn Error>> {
+            if self.runs.fetch_add(1, Ordering::Relaxed) > 0 {
+                panic!("Unknown operation");
+            }
+            Ok(0.into())
+        }
+
+        fn run_part_2(&self, _input: &(), _ctx: &RunContext) -> Result<Answer, Box<dyn Error>> {
+            if self.runs.fetch_add(1, Ordering::Relaxed) > 0 {
+                std::thread::sleep(Duration::from_secs(5));
+            }
+            Ok(0.into())
+        }
+    }
+
+    fn run(day: Box<dyn Day>, timeout: Option<Duration>, part: Part) -> Status {
+        let runner = Runner {
+            days: Vec::leak(vec![day]),
+            base_url: String::new(),
+            cache: InputCache::new(PathBuf::new()),
+            params: Params::new(),
+            budget: BenchmarkBudget::default(),
+            is_benchmarking: true,
+            compare: None,
+            max_regression: 0.0,
+            commit: String::new(),
+            timeout,
+            custom_input: None,
+        };
+        let target = RunTarget {
+            day: 1,
+            part,
+            input_type: PuzzleInputType::Example,
+        };
+        let ctx = RunContext::new(PuzzleInputType::Example);
+        runner
+            .run_isolated(
+                runner.days[0].as_ref(),
+                target,
+                &Source::Example(&EXAMPLE),
+                &ctx,
+            )
+            .0
+    }
+
+    fn flaky() -> Box<dyn Day> {
+        Box::new(Flaky {
+            runs: AtomicUsize::new(0),
+        })
+    }
+
+    #[test]
+    fn panics_are_captured() {
+        for timeout in [None, Some(Duration::from_secs(5))] {
+            for day in [Box::new(Faulty) as Box<dyn Day>, flaky()] {
+                match run(day, timeout, Part::One) {
+                    Status::Panicked(message) => assert_eq!(message, "Unknown operation"),
+                    _ => panic!("expected a panic to be reported"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slow_parts_time_out() {
+        let timeout = Duration::from_millis(50);
+        for day in [Box::new(Faulty) as Box<dyn Day>, flaky()] {
+            assert!(matches!(
+                run(day, Some(timeout), Part::Two),
+                Status::TimedOut(t) if t == timeout
+            ));
+        }
+    }
+}
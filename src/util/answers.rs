@@ -0,0 +1,134 @@
+use std::{error::Error, fmt::Display, path::PathBuf};
+
+use crate::{
+    day::Part,
+    util::{dirs::data_dir, input::PuzzleInputType},
+};
+
+/// Recorded answers for a single day, stored in `answers/{year}_{day}.toml`
+/// under the [data directory](data_dir).
+///
+/// ```toml
+/// [part1]
+/// actual = 1234
+/// ```
+pub struct AnswerStore {
+    path: PathBuf,
+    table: toml::Table,
+}
+
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong { expected } => write!(f, "wrong (expected {})", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl AnswerStore {
+    pub fn path(year: u16, day: u8) -> PathBuf {
+        data_dir()
+            .join("answers")
+            .join(format!("{}_{}.toml", year, day))
+    }
+
+    pub fn load(year: u16, day: u8) -> Result<Self, Box<dyn Error>> {
+        let path = Self::path(year, day);
+        let table = match std::fs::read_to_string(&path) {
+            Ok(content) => content
+                .parse::<toml::Table>()
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, table })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, toml::to_string(&self.table)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, part: Part, input_type: PuzzleInputType, answer: &str) {
+        let value = match answer.parse::<i64>() {
+            Ok(number) => toml::Value::Integer(number),
+            Err(_) => toml::Value::String(answer.to_owned()),
+        };
+        let section = self
+            .table
+            .entry(Self::part_key(part))
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let toml::Value::Table(section) = section {
+            section.insert(Self::input_key(input_type).to_owned(), value);
+        }
+    }
+
+    pub fn expected(&self, part: Part, input_type: PuzzleInputType) -> Option<String> {
+        let value = self
+            .table
+            .get(&Self::part_key(part))?
+            .get(Self::input_key(input_type))?;
+        match value {
+            toml::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    pub fn verify(&self, part: Part, input_type: PuzzleInputType, answer: &str) -> Verdict {
+        match self.expected(part, input_type) {
+            Some(expected) if expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong { expected },
+            None => Verdict::Unknown,
+        }
+    }
+
+    fn part_key(part: Part) -> String {
+        format!("part{}", part)
+    }
+
+    fn input_key(input_type: PuzzleInputType) -> &'static str {
+        match input_type {
+            PuzzleInputType::Example => "example",
+            PuzzleInputType::Actual => "actual",
+            PuzzleInputType::Custom => "custom",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verify_against_table() {
+        let store = AnswerStore {
+            path: PathBuf::new(),
+            table: "[part1]\nexample = 3\nactual = \"abc\"\n"
+                .parse::<toml::Table>()
+                .unwrap(),
+        };
+        assert!(matches!(
+            store.verify(Part::One, PuzzleInputType::Example, "3"),
+            Verdict::Correct
+        ));
+        assert!(matches!(
+            store.verify(Part::One, PuzzleInputType::Actual, "abd"),
+            Verdict::Wrong { .. }
+        ));
+        assert!(matches!(
+            store.verify(Part::Two, PuzzleInputType::Example, "3"),
+            Verdict::Unknown
+        ));
+    }
+}
@@ -1,18 +1,125 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Solve,
+}
+
 pub trait Benchmarker {
-    fn start_benchmark(&mut self);
-    fn end_benchmark(&mut self);
-    /// Returns the benchmark time in milliseconds
-    fn elapsed_ms(&self) -> Option<f64>;
+    fn start_benchmark(&mut self, phase: Phase);
+    fn end_benchmark(&mut self, phase: Phase);
+    /// Returns the distribution of the samples of `phase`
+    fn stats(&self, phase: Phase) -> Option<Stats>;
     fn n(&self) -> usize;
+    /// Drops all samples collected so far, e.g. after warming up
+    fn reset(&mut self);
 }
 
-pub struct SimpleBenchmarker {
+/// How long the runner keeps repeating a part when benchmarking.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkBudget {
+    /// Time spent running the part before samples are collected.
+    pub warmup: Duration,
+    pub time: Duration,
+    pub runs: usize,
+}
+
+impl Default for BenchmarkBudget {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(200),
+            time: Duration::from_millis(2000),
+            runs: 1000,
+        }
+    }
+}
+
+impl BenchmarkBudget {
+    /// Repeats `run` until the budget is spent, discarding the samples taken
+    /// while warming up. `run` is called at least once after the warmup,
+    /// unless it returns `false` to stop early.
+    pub fn repeat(
+        &self,
+        benchmarker: &mut dyn Benchmarker,
+        mut run: impl FnMut(&mut dyn Benchmarker) -> bool,
+    ) {
+        let start = std::time::Instant::now();
+        while start.elapsed() < self.warmup {
+            if !run(benchmarker) {
+                return;
+            }
+        }
+        benchmarker.reset();
+
+        let start = std::time::Instant::now();
+        loop {
+            if !run(benchmarker) {
+                return;
+            }
+            if start.elapsed() >= self.time || benchmarker.n() >= self.runs {
+                break;
+            }
+        }
+    }
+}
+
+/// Distribution of the samples of a phase, in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub n: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub stddev: f64,
+    /// Samples outside 1.5 interquartile ranges of the quartiles.
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn from_durations(durations: &[Duration]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+        let mut samples: Vec<f64> = durations.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        samples.sort_by(f64::total_cmp);
+
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+        let percentile = |p: f64| samples[((p * n as f64).ceil() as usize).clamp(1, n) - 1];
+
+        let (q1, q3) = (percentile(0.25), percentile(0.75));
+        let fence = 1.5 * (q3 - q1);
+        let outliers = samples
+            .iter()
+            .filter(|&&s| s < q1 - fence || s > q3 + fence)
+            .count();
+
+        Some(Self {
+            n,
+            mean,
+            min: samples[0],
+            max: samples[n - 1],
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            stddev: variance.sqrt(),
+            outliers,
+        })
+    }
+}
+
+struct PhaseTimer {
     start_time: Option<std::time::Instant>,
-    durations: Vec<std::time::Duration>,
+    durations: Vec<Duration>,
 }
 
-impl SimpleBenchmarker {
-    pub fn new() -> Self {
+impl PhaseTimer {
+    fn new() -> Self {
         Self {
             start_time: None,
             durations: vec![],
@@ -20,30 +127,79 @@ impl SimpleBenchmarker {
     }
 }
 
+pub struct SimpleBenchmarker {
+    parse: PhaseTimer,
+    solve: PhaseTimer,
+}
+
+impl SimpleBenchmarker {
+    pub fn new() -> Self {
+        Self {
+            parse: PhaseTimer::new(),
+            solve: PhaseTimer::new(),
+        }
+    }
+
+    fn timer(&self, phase: Phase) -> &PhaseTimer {
+        match phase {
+            Phase::Parse => &self.parse,
+            Phase::Solve => &self.solve,
+        }
+    }
+
+    fn timer_mut(&mut self, phase: Phase) -> &mut PhaseTimer {
+        match phase {
+            Phase::Parse => &mut self.parse,
+            Phase::Solve => &mut self.solve,
+        }
+    }
+}
+
 impl Benchmarker for SimpleBenchmarker {
-    fn start_benchmark(&mut self) {
-        self.start_time = Some(std::time::Instant::now());
+    fn start_benchmark(&mut self, phase: Phase) {
+        self.timer_mut(phase).start_time = Some(std::time::Instant::now());
     }
 
-    fn end_benchmark(&mut self) {
-        if let Some(start) = self.start_time {
+    fn end_benchmark(&mut self, phase: Phase) {
+        let timer = self.timer_mut(phase);
+        if let Some(start) = timer.start_time {
             let duration = start.elapsed();
-            self.durations.push(duration);
-            self.start_time = None;
+            timer.durations.push(duration);
+            timer.start_time = None;
         }
     }
 
-    fn elapsed_ms(&self) -> Option<f64> {
-        if self.durations.is_empty() {
-            None
-        } else {
-            let total_duration: std::time::Duration = self.durations.iter().copied().sum();
-            let avg_duration = total_duration / (self.durations.len() as u32);
-            Some(avg_duration.as_secs_f64() * 1000.0)
-        }
+    fn stats(&self, phase: Phase) -> Option<Stats> {
+        Stats::from_durations(&self.timer(phase).durations)
     }
 
     fn n(&self) -> usize {
-        self.durations.len()
+        self.solve.durations.len()
+    }
+
+    fn reset(&mut self) {
+        self.parse = PhaseTimer::new();
+        self.solve = PhaseTimer::new();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats_of_samples() {
+        let mut durations: Vec<Duration> = (1..=19).map(Duration::from_millis).collect();
+        durations.push(Duration::from_millis(1000));
+        let stats = Stats::from_durations(&durations).unwrap();
+        assert_eq!(stats.n, 20);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 1000.0);
+        assert_eq!(stats.median, 10.0);
+        assert_eq!(stats.p95, 19.0);
+        assert_eq!(stats.p99, 1000.0);
+        assert_eq!(stats.outliers, 1);
+        assert!((stats.mean - 59.5).abs() < 1e-9);
+        assert!(Stats::from_durations(&[]).is_none());
     }
 }
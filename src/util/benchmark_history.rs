@@ -0,0 +1,299 @@
+use std::{error::Error, path::PathBuf};
+
+use crate::{
+    day::Part,
+    util::{benchmark::Stats, dirs::data_dir},
+};
+
+/// A benchmarked part, as stored in the history.
+pub struct BenchmarkRecord {
+    pub timestamp: u64,
+    pub commit: String,
+    pub day: u8,
+    pub part: u8,
+    /// `actual`, or the name of the example that was run.
+    pub input: String,
+    pub stats: Stats,
+}
+
+impl BenchmarkRecord {
+    fn to_line(&self) -> String {
+        let s = &self.stats;
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.timestamp,
+            self.commit,
+            self.day,
+            self.part,
+            self.input,
+            s.n,
+            s.mean,
+            s.min,
+            s.max,
+            s.median,
+            s.p95,
+            s.p99,
+            s.stddev,
+            s.outliers
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [
+            timestamp,
+            commit,
+            day,
+            part,
+            input,
+            n,
+            mean,
+            min,
+            max,
+            median,
+            p95,
+            p99,
+            stddev,
+            outliers,
+        ] = fields[..]
+        else {
+            return None;
+        };
+        Some(Self {
+            timestamp: timestamp.parse().ok()?,
+            commit: commit.to_owned(),
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            input: input.to_owned(),
+            stats: Stats {
+                n: n.parse().ok()?,
+                mean: mean.parse().ok()?,
+                min: min.parse().ok()?,
+                max: max.parse().ok()?,
+                median: median.parse().ok()?,
+                p95: p95.parse().ok()?,
+                p99: p99.parse().ok()?,
+                stddev: stddev.parse().ok()?,
+                outliers: outliers.parse().ok()?,
+            },
+        })
+    }
+}
+
+/// Which earlier run a benchmark is compared against.
+pub enum Baseline {
+    /// The latest recorded run of the part.
+    Latest,
+    /// The latest run of a commit, preferring runs without uncommitted
+    /// changes over those recorded as `<commit>-dirty`.
+    Commit(String),
+}
+
+/// Local history of benchmark runs, stored in `benchmarks/history.log` under
+/// the [data directory](data_dir).
+///
+/// Each line holds the unix timestamp, commit, day, part, input and the
+/// solve time statistics in milliseconds, separated by tabs.
+pub struct BenchmarkHistory {
+    path: PathBuf,
+    records: Vec<BenchmarkRecord>,
+}
+
+impl BenchmarkHistory {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let path = data_dir().join("benchmarks").join("history.log");
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let records = content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(BenchmarkRecord::from_line)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| format!("Malformed benchmark history {}", path.display()))?;
+        Ok(Self { path, records })
+    }
+
+    /// Returns the latest record of the part matching `baseline`.
+    pub fn baseline(
+        &self,
+        day: u8,
+        part: Part,
+        input: &str,
+        baseline: &Baseline,
+    ) -> Option<&BenchmarkRecord> {
+        let mut runs = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| r.day == day && r.part == part.to_number() && r.input == input);
+        match baseline {
+            Baseline::Latest => runs.next(),
+            Baseline::Commit(commit) => runs
+                .clone()
+                .find(|r| &r.commit == commit)
+                .or_else(|| runs.find(|r| r.commit.strip_suffix("-dirty") == Some(commit))),
+        }
+    }
+
+    pub fn append(&mut self, record: BenchmarkRecord) -> Result<(), Box<dyn Error>> {
+        use std::io::Write;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", record.to_line())?;
+        self.records.push(record);
+        Ok(())
+    }
+}
+
+/// Resolves a git revision to its short commit hash.
+pub fn resolve_commit(revision: &str) -> Result<String, Box<dyn Error>> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", revision])
+        .output()?;
+    if !output.status.success() {
+        return Err(format!("Unknown revision {}", revision).into());
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+}
+
+/// The commit benchmarks are recorded under, marked `-dirty` when the
+/// working tree has uncommitted changes.
+pub fn current_commit() -> String {
+    let Ok(commit) = resolve_commit("HEAD") else {
+        return "unknown".to_owned();
+    };
+    let is_dirty = std::process::Command::new("git")
+        .args(["status", "--porcelain"])
+        .output()
+        .is_ok_and(|output| !output.stdout.is_empty());
+    if is_dirty {
+        format!("{}-dirty", commit)
+    } else {
+        commit
+    }
+}
+
+/// Change of the mean solve time relative to a baseline.
+pub struct Comparison {
+    pub baseline_mean: f64,
+    pub mean: f64,
+    /// Positive when slower than the baseline.
+    pub change_percent: f64,
+    /// Whether the difference exceeds two standard errors of the means.
+    pub is_significant: bool,
+}
+
+impl Comparison {
+    pub fn new(baseline: &Stats, current: &Stats) -> Self {
+        let standard_error = (baseline.stddev.powi(2) / baseline.n as f64
+            + current.stddev.powi(2) / current.n as f64)
+            .sqrt();
+        let difference = current.mean - baseline.mean;
+        Self {
+            baseline_mean: baseline.mean,
+            mean: current.mean,
+            change_percent: difference / baseline.mean * 100.0,
+            is_significant: difference.abs() > 2.0 * standard_error,
+        }
+    }
+
+    /// Whether this is a significant slowdown of more than `max_percent`.
+    pub fn is_regression(&self, max_percent: f64) -> bool {
+        self.is_significant && self.change_percent > max_percent
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats(n: usize, mean: f64, stddev: f64) -> Stats {
+        Stats {
+            n,
+            mean,
+            min: mean,
+            max: mean,
+            median: mean,
+            p95: mean,
+            p99: mean,
+            stddev,
+            outliers: 0,
+        }
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let record = BenchmarkRecord {
+            timestamp: 1,
+            commit: "abc1234".to_owned(),
+            day: 3,
+            part: 2,
+            input: "actual".to_owned(),
+            stats: stats(100, 1.25, 0.5),
+        };
+        let parsed = BenchmarkRecord::from_line(&record.to_line()).unwrap();
+        assert_eq!(parsed.commit, "abc1234");
+        assert_eq!(parsed.input, "actual");
+        assert_eq!(parsed.stats, record.stats);
+        assert!(BenchmarkRecord::from_line("1\tabc").is_none());
+    }
+
+    #[test]
+    fn baselines() {
+        let record = |timestamp, commit: &str, part| BenchmarkRecord {
+            timestamp,
+            commit: commit.to_owned(),
+            day: 3,
+            part,
+            input: "actual".to_owned(),
+            stats: stats(100, 1.0, 0.5),
+        };
+        let history = BenchmarkHistory {
+            path: PathBuf::new(),
+            records: vec![
+                record(1, "abc1234", 1),
+                record(2, "abc1234-dirty", 1),
+                record(3, "def5678-dirty", 1),
+                record(4, "def5678-dirty", 2),
+            ],
+        };
+        let baseline = |part, baseline: Baseline| {
+            history
+                .baseline(3, part, "actual", &baseline)
+                .map(|r| r.timestamp)
+        };
+        assert_eq!(baseline(Part::One, Baseline::Latest), Some(3));
+        let commit = |commit: &str| Baseline::Commit(commit.to_owned());
+        assert_eq!(baseline(Part::One, commit("abc1234")), Some(1));
+        assert_eq!(baseline(Part::One, commit("def5678")), Some(3));
+        assert_eq!(baseline(Part::Two, commit("abc1234")), None);
+        assert_eq!(baseline(Part::One, commit("def567")), None);
+    }
+
+    #[test]
+    fn significance_and_regressions() {
+        let baseline = stats(100, 10.0, 1.0);
+        let noisy = Comparison::new(&baseline, &stats(100, 10.2, 1.0));
+        assert!(!noisy.is_significant);
+        assert!(!noisy.is_regression(1.0));
+
+        let slower = Comparison::new(&baseline, &stats(100, 12.0, 1.0));
+        assert!(slower.is_significant);
+        assert!((slower.change_percent - 20.0).abs() < 1e-9);
+        assert!(slower.is_regression(10.0));
+        assert!(!slower.is_regression(25.0));
+
+        let faster = Comparison::new(&baseline, &stats(100, 8.0, 1.0));
+        assert!(faster.is_significant);
+        assert!(!faster.is_regression(0.0));
+    }
+}
@@ -0,0 +1,14 @@
+use std::path::{Path, PathBuf};
+
+/// Resolves the directory holding recorded answers, submissions and
+/// benchmarks, preferring the `AOC_DATA_DIR` environment variable over the
+/// XDG data directory.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("AOC_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .map_or_else(|| PathBuf::from("."), |dir| dir.join("aoc25"))
+}
@@ -0,0 +1,121 @@
+/// A disjoint-set forest over the elements `0..len`, merging components by
+/// size and compressing paths on lookup.
+#[derive(Debug, Clone)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    /// Size of the component, valid for roots only.
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    /// Every element in a component of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            components: len,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of the component containing `element`.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut element = element;
+        while self.parent[element] != root {
+            element = std::mem::replace(&mut self.parent[element], root);
+        }
+        root
+    }
+
+    /// Merges the components of `a` and `b`, returning whether they were
+    /// apart.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (large, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.components -= 1;
+        true
+    }
+
+    #[allow(unused)]
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The size of the component containing `element`.
+    #[allow(unused)]
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    /// The number of components, counting single elements.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// The sizes of all components, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.parent.len())
+            .filter(|&element| self.parent[element] == element)
+            .map(|root| self.size[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn union_and_find() {
+        let mut dsu = Dsu::new(6);
+        assert_eq!(dsu.components(), 6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 4));
+        assert_eq!(dsu.size(2), 4);
+        assert_eq!(dsu.size(5), 1);
+        assert_eq!(dsu.components(), 3);
+        assert_eq!(dsu.component_sizes(), [4, 1, 1]);
+    }
+
+    #[test]
+    fn long_chains_stay_shallow() {
+        let mut dsu = Dsu::new(10_000);
+        for element in 1..10_000 {
+            dsu.union(element - 1, element);
+        }
+        let root = dsu.find(0);
+        assert!((0..10_000).all(|element| dsu.parent[dsu.parent[element]] == root));
+        assert_eq!(dsu.components(), 1);
+        assert_eq!(dsu.component_sizes(), [10_000]);
+    }
+}
@@ -0,0 +1,572 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::Display,
+};
+
+/// Why paths could not be counted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCountError {
+    /// A cycle lies on a route between the endpoints, so there are infinitely
+    /// many paths.
+    Cycle,
+    /// There are more paths than fit a `u128`.
+    Overflow,
+}
+
+impl Display for PathCountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathCountError::Cycle => write!(f, "a cycle allows infinitely many paths"),
+            PathCountError::Overflow => write!(f, "too many paths to count"),
+        }
+    }
+}
+
+impl Error for PathCountError {}
+
+/// A node of a [`Graph`], numbered in the order the nodes were added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A directed graph whose nodes are labeled, e.g. with names from the puzzle
+/// input. Each label is interned once, so the algorithms work on dense
+/// [`NodeId`]s.
+#[derive(Debug, Clone, Default)]
+pub struct Graph<'a> {
+    labels: Vec<&'a [u8]>,
+    ids: HashMap<&'a [u8], NodeId>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The node labeled `label`, added if it is new.
+    pub fn node(&mut self, label: &'a [u8]) -> NodeId {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+        let id = NodeId(self.labels.len());
+        self.labels.push(label);
+        self.ids.insert(label, id);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    /// The node labeled `label`, if there is one.
+    pub fn id(&self, label: &[u8]) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: NodeId) -> &'a [u8] {
+        self.labels[id.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl ExactSizeIterator<Item = NodeId> + use<> {
+        (0..self.len()).map(NodeId)
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.adjacency[from.0].push(to.0);
+    }
+
+    pub fn successors(&self, id: NodeId) -> impl ExactSizeIterator<Item = NodeId> + '_ {
+        self.adjacency[id.0].iter().map(|&n| NodeId(n))
+    }
+
+    /// One cycle through each strongly connected component that has any, so
+    /// that separate loops are all found. The cycles are ordered by the
+    /// first added node on them.
+    pub fn cycles(&self) -> Vec<Vec<NodeId>> {
+        let mut cycles: Vec<Vec<NodeId>> = self
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| {
+                let node = component[0];
+                component.len() > 1 || self.successors(node).any(|n| n == node)
+            })
+            .filter_map(|component| {
+                let mut inside = vec![false; self.len()];
+                for node in &component {
+                    inside[node.0] = true;
+                }
+                let adjacency: Vec<Vec<usize>> = (0..self.len())
+                    .map(|node| {
+                        let successors = self.adjacency[node].iter().copied();
+                        successors.filter(|&s| inside[node] && inside[s]).collect()
+                    })
+                    .collect();
+                find_cycle(&adjacency)
+            })
+            .map(|cycle| cycle.into_iter().map(NodeId).collect())
+            .collect();
+        cycles.sort_by_key(|cycle| cycle.iter().min().copied());
+        cycles
+    }
+
+    /// Groups the nodes into strongly connected components, the maximal sets
+    /// of nodes that can all reach each other, using Tarjan's algorithm.
+    /// Components come after all components they lead to.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; self.len()];
+        let mut low_link = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..self.len() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // The nodes being visited, with how many successors of each were tried.
+            let mut visiting = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, tried)) = visiting.last_mut() {
+                let node = *node;
+                if let Some(&successor) = self.adjacency[node].get(*tried) {
+                    *tried += 1;
+                    if index[successor] == UNVISITED {
+                        index[successor] = next_index;
+                        low_link[successor] = next_index;
+                        next_index += 1;
+                        stack.push(successor);
+                        on_stack[successor] = true;
+                        visiting.push((successor, 0));
+                    } else if on_stack[successor] {
+                        low_link[node] = low_link[node].min(index[successor]);
+                    }
+                    continue;
+                }
+
+                visiting.pop();
+                if let Some(&(parent, _)) = visiting.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+                if low_link[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(NodeId(member));
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Which nodes can be reached from any of `starts`, indexed by
+    /// [`NodeId::index`].
+    pub fn reachable(&self, starts: impl IntoIterator<Item = NodeId>) -> Vec<bool> {
+        reachable(&self.adjacency, starts.into_iter().map(|id| id.0))
+    }
+
+    /// Counts the paths from `from` to `to` that pass through all of
+    /// `waypoints`, in any order, and through none of `avoid`.
+    ///
+    /// Unless cycles make the count infinite, every such path meets the
+    /// waypoints in the same order, the one in which they reach each other.
+    /// The paths are then counted leg by leg along that order.
+    pub fn count_paths_visiting(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        waypoints: &[NodeId],
+        avoid: &[NodeId],
+    ) -> Result<u128, PathCountError> {
+        let mut blocked = vec![false; self.len()];
+        for node in avoid {
+            blocked[node.0] = true;
+        }
+        if [from, to]
+            .iter()
+            .chain(waypoints)
+            .any(|node| blocked[node.0])
+        {
+            return Ok(0);
+        }
+        let adjacency: Vec<Vec<usize>> = (0..self.len())
+            .map(|node| {
+                let successors = self.adjacency[node].iter().copied();
+                successors
+                    .filter(|&s| !blocked[node] && !blocked[s])
+                    .collect()
+            })
+            .collect();
+
+        let mut stops: Vec<usize> = waypoints
+            .iter()
+            .map(|node| node.0)
+            .filter(|&node| node != from.0 && node != to.0)
+            .collect();
+        stops.sort_unstable();
+        stops.dedup();
+        // A waypoint comes before those it reaches. If some pair does not
+        // reach each other, one of the legs below has no paths.
+        let reaches: Vec<usize> = stops
+            .iter()
+            .map(|&stop| {
+                let reached = reachable(&adjacency, [stop]);
+                stops.iter().filter(|&&other| reached[other]).count()
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..stops.len()).collect();
+        order.sort_by_key(|&i| Reverse(reaches[i]));
+
+        let route: Vec<usize> = std::iter::once(from.0)
+            .chain(order.into_iter().map(|i| stops[i]))
+            .chain(std::iter::once(to.0))
+            .collect();
+        let legs: Vec<Result<u128, PathCountError>> = route
+            .windows(2)
+            .map(|leg| count_paths(&adjacency, leg[0], leg[1]))
+            .collect();
+        // Cycles only allow infinitely many paths if there are any at all.
+        if legs.contains(&Ok(0)) {
+            return Ok(0);
+        }
+        legs.into_iter().try_fold(1u128, |count, leg| {
+            count.checked_mul(leg?).ok_or(PathCountError::Overflow)
+        })
+    }
+}
+
+/// Counts the paths from `from` to `to` exactly, given the successors of each
+/// node `0..adjacency.len()`.
+///
+/// Only nodes on some route between the two take part, so cycles elsewhere
+/// in the graph do not matter. Those nodes are visited in topological order,
+/// adding up the number of paths reaching each.
+pub fn count_paths(
+    adjacency: &[Vec<usize>],
+    from: usize,
+    to: usize,
+) -> Result<u128, PathCountError> {
+    let mut predecessors = vec![Vec::new(); adjacency.len()];
+    for (node, successors) in adjacency.iter().enumerate() {
+        for &successor in successors {
+            predecessors[successor].push(node);
+        }
+    }
+    let reached = reachable(adjacency, [from]);
+    let relevant: Vec<bool> = reachable(&predecessors, [to])
+        .iter()
+        .zip(&reached)
+        .map(|(&leads_to, &reached)| leads_to && reached)
+        .collect();
+
+    let mut in_degree = vec![0usize; adjacency.len()];
+    for (node, successors) in adjacency.iter().enumerate() {
+        if !relevant[node] {
+            continue;
+        }
+        for &successor in successors.iter().filter(|&&s| relevant[s]) {
+            in_degree[successor] += 1;
+        }
+    }
+
+    let mut paths = vec![0u128; adjacency.len()];
+    let mut remaining = relevant.iter().filter(|&&r| r).count();
+    let mut ready: VecDeque<usize> = (0..adjacency.len())
+        .filter(|&node| relevant[node] && in_degree[node] == 0)
+        .collect();
+    if relevant[from] {
+        paths[from] = 1;
+    }
+    while let Some(node) = ready.pop_front() {
+        remaining -= 1;
+        for &successor in adjacency[node].iter().filter(|&&s| relevant[s]) {
+            paths[successor] = paths[successor]
+                .checked_add(paths[node])
+                .ok_or(PathCountError::Overflow)?;
+            in_degree[successor] -= 1;
+            if in_degree[successor] == 0 {
+                ready.push_back(successor);
+            }
+        }
+    }
+    if remaining > 0 {
+        return Err(PathCountError::Cycle);
+    }
+    Ok(paths[to])
+}
+
+/// Which nodes can be reached from any of `starts`, including themselves.
+pub fn reachable(adjacency: &[Vec<usize>], starts: impl IntoIterator<Item = usize>) -> Vec<bool> {
+    let mut reached = vec![false; adjacency.len()];
+    let mut stack = Vec::new();
+    for start in starts {
+        if !reached[start] {
+            reached[start] = true;
+            stack.push(start);
+        }
+    }
+    while let Some(node) = stack.pop() {
+        for &successor in &adjacency[node] {
+            if !reached[successor] {
+                reached[successor] = true;
+                stack.push(successor);
+            }
+        }
+    }
+    reached
+}
+
+/// The nodes along some cycle, in order, if the graph has any.
+pub fn find_cycle(adjacency: &[Vec<usize>]) -> Option<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        OnPath,
+        Done,
+    }
+
+    let mut state = vec![State::Unvisited; adjacency.len()];
+    for start in 0..adjacency.len() {
+        if state[start] != State::Unvisited {
+            continue;
+        }
+        // The current path, with how many successors of each node were tried.
+        let mut path = vec![(start, 0)];
+        state[start] = State::OnPath;
+        while let Some((node, tried)) = path.last_mut() {
+            let Some(&successor) = adjacency[*node].get(*tried) else {
+                state[*node] = State::Done;
+                path.pop();
+                continue;
+            };
+            *tried += 1;
+            match state[successor] {
+                State::Unvisited => {
+                    state[successor] = State::OnPath;
+                    path.push((successor, 0));
+                }
+                State::OnPath => {
+                    let cycle_start = path.iter().position(|&(n, _)| n == successor).unwrap();
+                    return Some(path[cycle_start..].iter().map(|&(n, _)| n).collect());
+                }
+                State::Done => {}
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graph(edges: &[(&'static str, &'static str)]) -> Graph<'static> {
+        let mut graph = Graph::new();
+        for &(from, to) in edges {
+            let (from, to) = (graph.node(from.as_bytes()), graph.node(to.as_bytes()));
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    fn labels(graph: &Graph, ids: &[NodeId]) -> Vec<String> {
+        ids.iter()
+            .map(|&id| String::from_utf8_lossy(graph.label(id)).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn labels_are_interned() {
+        let mut graph = graph(&[("a", "b"), ("b", "c")]);
+        let b = graph.id(b"b").unwrap();
+        assert_eq!(graph.node(b"b"), b);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.id(b"d"), None);
+        assert_eq!(
+            labels(&graph, &graph.successors(b).collect::<Vec<_>>()),
+            ["c"]
+        );
+    }
+
+    #[test]
+    fn components_and_cycles() {
+        let dag = graph(&[("c", "d"), ("a", "c"), ("b", "c")]);
+        assert_eq!(dag.strongly_connected_components().len(), 4);
+        assert!(dag.cycles().is_empty());
+
+        let cyclic = graph(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "d"),
+            ("e", "f"),
+            ("f", "f"),
+        ]);
+        let components: Vec<Vec<String>> = cyclic
+            .strongly_connected_components()
+            .iter()
+            .map(|component| {
+                let mut labels = labels(&cyclic, component);
+                labels.sort();
+                labels
+            })
+            .collect();
+        assert_eq!(components, [vec!["f"], vec!["d", "e"], vec!["a", "b", "c"]]);
+        let cycles: Vec<Vec<String>> = cyclic
+            .cycles()
+            .iter()
+            .map(|cycle| labels(&cyclic, cycle))
+            .collect();
+        assert_eq!(cycles, [vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]);
+    }
+
+    #[test]
+    fn reachable_nodes() {
+        let graph = graph(&[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("e", "a")]);
+        let [a, b, c] = [b"a", b"b", b"c"].map(|label| graph.id(label).unwrap());
+        assert_eq!(graph.reachable([b, c]), [false, true, true, true, false]);
+        assert_eq!(graph.reachable([a]), [true, true, true, true, false]);
+    }
+
+    #[test]
+    fn counts_paths_through_waypoints_in_any_order() {
+        let graph = graph(&[
+            ("svr", "aaa"),
+            ("svr", "bbb"),
+            ("aaa", "dac"),
+            ("bbb", "dac"),
+            ("dac", "fft"),
+            ("dac", "out"),
+            ("fft", "out"),
+            ("fft", "ccc"),
+            ("ccc", "out"),
+        ]);
+        let id = |label: &str| graph.id(label.as_bytes()).unwrap();
+        let (svr, out, dac, fft) = (id("svr"), id("out"), id("dac"), id("fft"));
+        assert_eq!(graph.count_paths_visiting(svr, out, &[], &[]), Ok(6));
+        assert_eq!(
+            graph.count_paths_visiting(svr, out, &[fft, dac], &[]),
+            Ok(4)
+        );
+        assert_eq!(
+            graph.count_paths_visiting(svr, out, &[dac, fft], &[]),
+            Ok(4)
+        );
+        assert_eq!(
+            graph.count_paths_visiting(svr, out, &[fft], &[id("aaa")]),
+            Ok(2)
+        );
+        assert_eq!(
+            graph.count_paths_visiting(svr, out, &[fft], &[id("ccc")]),
+            Ok(2)
+        );
+        assert_eq!(graph.count_paths_visiting(svr, out, &[dac], &[dac]), Ok(0));
+        assert_eq!(
+            graph.count_paths_visiting(svr, out, &[id("aaa"), id("bbb")], &[]),
+            Ok(0)
+        );
+        assert_eq!(graph.count_paths_visiting(svr, svr, &[svr], &[]), Ok(1));
+
+        // A loop only matters when it lies on a path through the waypoints.
+        let mut graph = graph.clone();
+        let ccc = graph.id(b"ccc").unwrap();
+        graph.add_edge(ccc, fft);
+        assert_eq!(
+            graph.count_paths_visiting(svr, out, &[fft], &[]),
+            Err(PathCountError::Cycle)
+        );
+        assert_eq!(graph.count_paths_visiting(svr, out, &[fft], &[ccc]), Ok(2));
+        assert_eq!(graph.count_paths_visiting(svr, dac, &[fft], &[]), Ok(0));
+    }
+
+    #[test]
+    fn counts_paths_in_a_dag() {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3, 0 -> 3, and a dead end 1 -> 4.
+        let adjacency = vec![vec![1, 2, 3], vec![3, 4], vec![3], vec![], vec![]];
+        assert_eq!(count_paths(&adjacency, 0, 3), Ok(3));
+        assert_eq!(count_paths(&adjacency, 3, 0), Ok(0));
+        assert_eq!(count_paths(&adjacency, 2, 2), Ok(1));
+    }
+
+    #[test]
+    fn cycles_only_matter_between_the_endpoints() {
+        // 0 -> 1 -> 2 with a loop 1 <-> 3 on the way.
+        let adjacency = vec![vec![1], vec![2, 3], vec![], vec![1]];
+        assert_eq!(count_paths(&adjacency, 0, 2), Err(PathCountError::Cycle));
+
+        // 0 -> 1 -> 2 with a loop at 3 behind the end, and a loop through
+        // the end, which allows paths around it any number of times.
+        let adjacency = vec![vec![1], vec![2], vec![3], vec![3]];
+        assert_eq!(count_paths(&adjacency, 0, 2), Ok(1));
+        let adjacency = vec![vec![1], vec![2], vec![3], vec![2]];
+        assert_eq!(count_paths(&adjacency, 0, 2), Err(PathCountError::Cycle));
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let adjacency = vec![vec![1, 2], vec![3], vec![3], vec![]];
+        assert_eq!(find_cycle(&adjacency), None);
+
+        let adjacency = vec![vec![1], vec![2], vec![3, 4], vec![], vec![1]];
+        assert_eq!(find_cycle(&adjacency), Some(vec![1, 2, 4]));
+        assert_eq!(find_cycle(&[vec![0]]), Some(vec![0]));
+    }
+
+    #[test]
+    fn reachable_from_several_starts() {
+        let adjacency = vec![vec![1], vec![], vec![3], vec![], vec![0]];
+        assert_eq!(
+            reachable(&adjacency, [0, 2]),
+            [true, true, true, true, false]
+        );
+    }
+
+    #[test]
+    fn counts_beyond_floating_point_precision() {
+        // 40 layers of three nodes, each connected to all of the next layer.
+        let layers = 40;
+        let mut adjacency = vec![vec![1, 2, 3]];
+        for layer in 0..layers {
+            let next = (0..3).map(|i| 1 + (layer + 1) * 3 + i);
+            let next: Vec<usize> = if layer + 1 == layers {
+                vec![1 + layers * 3]
+            } else {
+                next.collect()
+            };
+            adjacency.extend(std::iter::repeat_n(next, 3));
+        }
+        adjacency.push(vec![]);
+
+        let count = count_paths(&adjacency, 0, adjacency.len() - 1).unwrap();
+        assert_eq!(count, 3u128.pow(40));
+        assert_ne!(count as f64 as u128, count);
+    }
+}
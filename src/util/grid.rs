@@ -0,0 +1,344 @@
+use std::{
+    fmt::Display,
+    iter::StepBy,
+    ops::{Index, IndexMut},
+    slice,
+};
+
+use crate::error::ParseError;
+
+/// Offsets to the four orthogonal neighbours, clockwise from above.
+pub static DIRECTIONS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all eight neighbours, clockwise from the upper left.
+pub static DIRECTIONS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid stored row by row, indexed by `(x, y)` with `(0, 0)` in
+/// the upper left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// A grid of the given size with every cell computed from its position.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// A grid of the given width holding `cells` row by row.
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            cells.len().is_multiple_of(width),
+            "{} cells do not fill rows of width {}",
+            cells.len(),
+            width
+        );
+        Self {
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+            width,
+        }
+    }
+
+    /// Parses the lines of `input` as the rows of a grid, converting each
+    /// byte with `cell`. Bytes it rejects are reported as not being one of
+    /// `expected`, e.g. `"'#' or '.'"`.
+    pub fn parse(
+        input: &[u8],
+        expected: &str,
+        cell: impl FnMut(u8) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let rows: Vec<&[u8]> = input.split(|&c| c == b'\n').collect();
+        Self::parse_rows(input, &rows, expected, cell)
+    }
+
+    /// Like [`Grid::parse`], for rows that were already split from `input`.
+    pub fn parse_rows(
+        input: &[u8],
+        rows: &[&[u8]],
+        expected: &str,
+        mut cell: impl FnMut(u8) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            if row.len() != width {
+                return Err(ParseError::at(
+                    input,
+                    row,
+                    format!("expected a row of width {}", width),
+                ));
+            }
+            for (i, &c) in row.iter().enumerate() {
+                let value = cell(c).ok_or_else(|| {
+                    ParseError::at(input, &row[i..=i], format!("expected {}", expected))
+                })?;
+                cells.push(value);
+            }
+        }
+        Ok(Self {
+            cells,
+            width,
+            height: rows.len(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// The position `offset` away from `(x, y)`, if it lies within the grid.
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx).filter(|&x| x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|&y| y < self.height)?;
+        Some((x, y))
+    }
+
+    /// The orthogonal neighbours of a position that lie within the grid.
+    #[allow(unused)]
+    pub fn neighbours_4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_4
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+    }
+
+    /// The orthogonal and diagonal neighbours of a position that lie within
+    /// the grid.
+    pub fn neighbours_8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_8
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[T]> + DoubleEndedIterator {
+        // `chunks_exact` panics on a chunk size of zero, even without cells.
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    /// The cells of column `x`, from top to bottom.
+    pub fn column(&self, x: usize) -> Column<'_, T> {
+        assert!(x < self.width, "column {} out of {}", x, self.width);
+        Column {
+            cells: self.cells[x..].iter().step_by(self.width),
+        }
+    }
+
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = Column<'_, T>> + DoubleEndedIterator {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// All positions row by row, together with their cells.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// The positions of all cells equal to `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(position, _)| position)
+    }
+
+    /// Renders the grid one row per line, each cell as a single character.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut rendered = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            rendered.extend(row.iter().map(&mut cell));
+            rendered.push('\n');
+        }
+        rendered
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// The grid mirrored along its main diagonal, so rows become columns.
+    pub fn transposed(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// The grid rotated clockwise by a quarter turn.
+    pub fn rotated(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flipped(&self) -> Self {
+        Self::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width, "x {} out of {}", x, self.width);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width, "x {} out of {}", x, self.width);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Prints the grid as the characters it was parsed from.
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(|&c| c as char))
+    }
+}
+
+/// Prints set cells as `#` and the others as `.`.
+impl Display for Grid<bool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(|&set| if set { '#' } else { '.' }))
+    }
+}
+
+/// The cells of one column of a [`Grid`], created by [`Grid::column`].
+#[derive(Clone)]
+pub struct Column<'a, T> {
+    cells: StepBy<slice::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Column<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cells.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Column<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cells.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Column<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(input: &[u8]) -> Grid<u8> {
+        Grid::parse(input, "anything", Some).unwrap()
+    }
+
+    #[test]
+    fn parse_and_print() {
+        let grid = grid(b"ab.\n.#c");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], b'c');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "ab.\n.#c\n");
+
+        let input = b"#.\n.x";
+        let error = Grid::parse(input, "'#' or '.'", |c| match c {
+            b'#' => Some(true),
+            b'.' => Some(false),
+            _ => None,
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.message, "expected '#' or '.'");
+
+        let error = Grid::parse(b"..\n.", "", Some).unwrap_err();
+        assert_eq!(error.message, "expected a row of width 2");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(
+            grid.neighbours_4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours_8((1, 0)).count(), 5);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 5);
+        assert_eq!(grid.offset((2, 1), (1, 0)), None);
+    }
+
+    #[test]
+    fn views_and_transformations() {
+        let grid = grid(b"abc\ndef");
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), b"be");
+        assert_eq!(grid.columns().len(), 3);
+        assert_eq!(grid.transposed().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotated().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.flipped().to_string(), "cba\nfed\n");
+        assert_eq!(grid.rotated().rotated().rotated().rotated(), grid);
+
+        let marked = Grid::from_cells(2, vec![true, false, false, true]);
+        assert_eq!(marked.find_all(&true).collect::<Vec<_>>(), [(0, 0), (1, 1)]);
+        assert_eq!(marked.to_string(), "#.\n.#\n");
+    }
+}
@@ -0,0 +1,534 @@
+use std::{error::Error, fmt::Display};
+
+/// Why a system could not be solved exactly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolveError {
+    /// Eliminating or searching needs numbers beyond `i128`, or sums
+    /// beyond `u64`.
+    Overflow,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Overflow => write!(f, "the system's numbers grow too large to solve"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// Finds non-negative integers `x` with `matrix · x = targets` whose sum is
+/// as small as possible, or `None` if there are none. Each row of `matrix`
+/// holds the coefficients of one equation.
+///
+/// The system is brought into reduced row echelon form over the rationals,
+/// kept as integer rows to stay exact. Every solution is then determined by
+/// the values of the free variables, which are searched branch and bound
+/// within the limits the targets put on them.
+///
+/// All arithmetic is exact. Elimination multiplies rows by each other's
+/// coefficients, and the search scales its bounds by all pivots, so systems
+/// with many large coefficients fail with [`SolveError::Overflow`] rather
+/// than giving a wrong minimum. Coefficients and targets of a few thousand
+/// in a dozen equations stay far within range.
+pub fn minimize_sum(matrix: &[Vec<u64>], targets: &[u64]) -> Result<Option<Vec<u64>>, SolveError> {
+    assert_eq!(matrix.len(), targets.len(), "one target per equation");
+    let variables = matrix.first().map_or(0, Vec::len);
+    assert!(
+        matrix.iter().all(|row| row.len() == variables),
+        "equations with different numbers of variables"
+    );
+
+    // With non-negative coefficients, a variable times its coefficient cannot
+    // exceed the target of any equation it appears in. Variables that appear
+    // in none are best left at zero.
+    let bounds: Vec<u64> = (0..variables)
+        .map(|variable| {
+            matrix
+                .iter()
+                .zip(targets)
+                .filter(|(row, _)| row[variable] > 0)
+                .map(|(row, &target)| target / row[variable])
+                .min()
+                .unwrap_or(0)
+        })
+        .collect();
+    // Sums of values never exceed the sum of their bounds.
+    bounds
+        .iter()
+        .try_fold(0u64, |sum, &bound| sum.checked_add(bound))
+        .ok_or(SolveError::Overflow)?;
+
+    let Some(mut echelon) = Echelon::reduce(matrix, targets)? else {
+        return Ok(None);
+    };
+    // The last free variable is solved for directly, so it should be the one
+    // with the most values to try.
+    echelon.order_free_by(|variable| bounds[variable]);
+    let mut search = Search::new(echelon, &bounds)?;
+    search.assign(0, 0);
+    Ok(search.best.map(|(_, values)| values))
+}
+
+/// A consistent system in reduced row echelon form, where row `i` reads
+/// `pivots[i].1 · x[pivots[i].0] + Σ coefficients[i][f] · x[free[f]] = constants[i]`.
+struct Echelon {
+    /// The variable each row solves for, with its coefficient, which is
+    /// positive.
+    pivots: Vec<(usize, i128)>,
+    free: Vec<usize>,
+    /// The coefficients of the free variables in each row.
+    coefficients: Vec<Vec<i128>>,
+    constants: Vec<i128>,
+}
+
+impl Echelon {
+    /// Eliminates the variables one by one, or returns `None` if the
+    /// equations contradict each other.
+    fn reduce(matrix: &[Vec<u64>], targets: &[u64]) -> Result<Option<Self>, SolveError> {
+        let variables = matrix.first().map_or(0, Vec::len);
+        // Each row with its target as the last entry.
+        let mut rows: Vec<Vec<i128>> = matrix
+            .iter()
+            .zip(targets)
+            .map(|(row, &target)| row.iter().chain([&target]).map(|&a| a as i128).collect())
+            .collect();
+        let mut pivots = Vec::new();
+        let mut free = Vec::new();
+        for variable in 0..variables {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..rows.len()).find(|&r| rows[r][variable] != 0) else {
+                free.push(variable);
+                continue;
+            };
+            rows.swap(rank, pivot);
+            if rows[rank][variable] < 0 {
+                rows[rank].iter_mut().for_each(|a| *a = -*a);
+            }
+            let pivot_row = rows[rank].clone();
+            let a = pivot_row[variable];
+            for (r, row) in rows.iter_mut().enumerate() {
+                let b = row[variable];
+                if r == rank || b == 0 {
+                    continue;
+                }
+                // Scaling by the positive pivot keeps the sign of the row's
+                // own pivot. Dividing out their common factor first keeps
+                // the numbers small.
+                let common = gcd(a.unsigned_abs(), b.unsigned_abs()) as i128;
+                let (a, b) = (a / common, b / common);
+                for (x, &p) in row.iter_mut().zip(&pivot_row) {
+                    *x = x
+                        .checked_mul(a)
+                        .zip(p.checked_mul(b))
+                        .and_then(|(x, p)| x.checked_sub(p))
+                        .ok_or(SolveError::Overflow)?;
+                }
+                let divisor = row.iter().fold(0, |d, &x| gcd(d, x.unsigned_abs()));
+                if divisor > 1 {
+                    row.iter_mut().for_each(|x| *x /= divisor as i128);
+                }
+            }
+            pivots.push((variable, a));
+        }
+
+        // The remaining rows have no coefficients left, so they only hold
+        // if their targets vanished as well.
+        if rows[pivots.len()..].iter().any(|row| row[variables] != 0) {
+            return Ok(None);
+        }
+        rows.truncate(pivots.len());
+        let coefficients = rows
+            .iter()
+            .map(|row| free.iter().map(|&f| row[f]).collect())
+            .collect();
+        let constants = rows.iter().map(|row| row[variables]).collect();
+        Ok(Some(Echelon {
+            pivots: pivots
+                .iter()
+                .zip(&rows)
+                .map(|(&(variable, _), row)| (variable, row[variable]))
+                .collect(),
+            free,
+            coefficients,
+            constants,
+        }))
+    }
+
+    /// Reorders the free variables by `key`, smallest first.
+    fn order_free_by(&mut self, key: impl Fn(usize) -> u64) {
+        let mut order: Vec<usize> = (0..self.free.len()).collect();
+        order.sort_by_key(|&f| key(self.free[f]));
+        self.free = order.iter().map(|&f| self.free[f]).collect();
+        for coefficients in &mut self.coefficients {
+            *coefficients = order.iter().map(|&f| coefficients[f]).collect();
+        }
+    }
+}
+
+struct Search {
+    echelon: Echelon,
+    /// The largest value of each free variable.
+    bounds: Vec<i128>,
+    /// How much the free variables from index `f` on can at most add to
+    /// each row's remainder, at `slack[f][row]`.
+    slack: Vec<Vec<i128>>,
+    /// The least common multiple of the pivots, by which the lower bounds
+    /// on the sum are scaled to stay integers.
+    scale: i128,
+    /// What a unit of each row's remainder adds to the scaled sum, which is
+    /// `scale` over the row's pivot.
+    shares: Vec<i128>,
+    /// The least the free variables from index `f` on can add to the scaled
+    /// sum, which is negative where they displace more pivot variables than
+    /// they add.
+    savings: Vec<i128>,
+    values: Vec<u64>,
+    /// What remains of each row's constant once the assigned free variables
+    /// are moved over, which the row's pivot variable has to make up.
+    remainders: Vec<i128>,
+    /// The smallest sum found so far, with its solution.
+    best: Option<(u64, Vec<u64>)>,
+}
+
+impl Search {
+    /// Prepares the search, failing if the remainders or the scaled sums
+    /// could leave `i128`.
+    fn new(echelon: Echelon, bounds: &[u64]) -> Result<Self, SolveError> {
+        let free = echelon.free.len();
+        let scale = echelon
+            .pivots
+            .iter()
+            .try_fold(1i128, |scale, &(_, pivot)| {
+                let common = gcd(scale.unsigned_abs(), pivot.unsigned_abs()) as i128;
+                (scale / common).checked_mul(pivot)
+            })
+            .ok_or(SolveError::Overflow)?;
+        let shares: Vec<i128> = echelon
+            .pivots
+            .iter()
+            .map(|&(_, pivot)| scale / pivot)
+            .collect();
+        // The scaled sums stay within the largest sum times `scale`, plus
+        // what the remainders and the savings add to it.
+        let mut extent = bounds
+            .iter()
+            .map(|&bound| bound as u128)
+            .sum::<u128>()
+            .checked_mul(scale.unsigned_abs())
+            .ok_or(SolveError::Overflow)?;
+        let bounds: Vec<i128> = echelon
+            .free
+            .iter()
+            .map(|&variable| bounds[variable] as i128)
+            .collect();
+        // Remainders stay within the constant plus everything the free
+        // variables can move, which also bounds the slack below.
+        for ((&constant, coefficients), &share) in echelon
+            .constants
+            .iter()
+            .zip(&echelon.coefficients)
+            .zip(&shares)
+        {
+            extent = coefficients
+                .iter()
+                .zip(&bounds)
+                .try_fold(constant.unsigned_abs(), |reach, (c, &bound)| {
+                    reach.checked_add(c.unsigned_abs().checked_mul(bound as u128)?)
+                })
+                .filter(|&reach| reach <= i128::MAX as u128)
+                .and_then(|reach| reach.checked_mul(share as u128))
+                .and_then(|reach| extent.checked_add(reach))
+                .ok_or(SolveError::Overflow)?;
+        }
+        let mut slack = vec![vec![0; echelon.pivots.len()]; free + 1];
+        let mut savings = vec![0i128; free + 1];
+        for f in (0..free).rev() {
+            let mut weight = scale;
+            for (r, coefficients) in echelon.coefficients.iter().enumerate() {
+                slack[f][r] = slack[f + 1][r] - coefficients[f].min(0) * bounds[f];
+                weight = coefficients[f]
+                    .checked_mul(shares[r])
+                    .and_then(|displaced| weight.checked_sub(displaced))
+                    .ok_or(SolveError::Overflow)?;
+            }
+            savings[f] = weight
+                .min(0)
+                .checked_mul(bounds[f])
+                .and_then(|saving| savings[f + 1].checked_add(saving))
+                .ok_or(SolveError::Overflow)?;
+        }
+        extent
+            .checked_add(savings[0].unsigned_abs())
+            .filter(|&extent| extent <= i128::MAX as u128)
+            .ok_or(SolveError::Overflow)?;
+        Ok(Search {
+            bounds,
+            slack,
+            scale,
+            shares,
+            savings,
+            values: vec![0; echelon.pivots.len() + free],
+            remainders: echelon.constants.clone(),
+            best: None,
+            echelon,
+        })
+    }
+
+    /// Tries all values of the free variables from index `f` on, given the
+    /// `sum` of those before it.
+    fn assign(&mut self, f: usize, sum: u64) {
+        let Some(&variable) = self.echelon.free.get(f) else {
+            self.complete(sum);
+            return;
+        };
+        let remainders = self.remainders.clone();
+        for value in self.range(f) {
+            if self
+                .best
+                .as_ref()
+                .is_some_and(|(best, _)| sum + value >= *best)
+            {
+                break;
+            }
+            self.values[variable] = value;
+            for ((remainder, &before), coefficients) in self
+                .remainders
+                .iter_mut()
+                .zip(&remainders)
+                .zip(&self.echelon.coefficients)
+            {
+                *remainder = before - coefficients[f] * value as i128;
+            }
+            if self.promising(f + 1, sum + value) {
+                self.assign(f + 1, sum + value);
+            }
+        }
+        self.values[variable] = 0;
+        self.remainders = remainders;
+    }
+
+    /// Whether the free variables from index `f` on might still complete a
+    /// solution with a smaller sum than the best one.
+    fn promising(&self, f: usize, sum: u64) -> bool {
+        let feasible = self
+            .remainders
+            .iter()
+            .zip(&self.slack[f])
+            .all(|(&remainder, &slack)| remainder + slack >= 0);
+        // The sum of the solution is `sum`, plus the remainders over the
+        // pivots, plus the weighted free variables left, all times `scale`.
+        let lowest = sum as i128 * self.scale
+            + self
+                .remainders
+                .iter()
+                .zip(&self.shares)
+                .map(|(&remainder, &share)| remainder * share)
+                .sum::<i128>()
+            + self.savings[f];
+        // Sums are integers, so improving needs a lowest sum of at most one
+        // below the best.
+        feasible
+            && self
+                .best
+                .as_ref()
+                .is_none_or(|&(best, _)| lowest <= (best as i128 - 1) * self.scale)
+    }
+
+    /// The values free variable `f` may take. Once it is the last one, every
+    /// row limits it to those leaving its pivot variable non-negative.
+    fn range(&self, f: usize) -> impl Iterator<Item = u64> + use<> {
+        let (mut low, mut high) = (0, self.bounds[f]);
+        if f + 1 == self.echelon.free.len() {
+            for (&remainder, coefficients) in self.remainders.iter().zip(&self.echelon.coefficients)
+            {
+                match coefficients[f] {
+                    0 if remainder < 0 => high = -1,
+                    0 => {}
+                    c if c > 0 => high = high.min(remainder.div_euclid(c)),
+                    c => low = low.max((-c - 1 - remainder).div_euclid(-c)),
+                }
+            }
+        }
+        (low..=high).map(|value| value as u64)
+    }
+
+    /// Solves for the pivot variables once all free ones are set.
+    fn complete(&mut self, sum: u64) {
+        let mut total = sum;
+        for (&remainder, &(variable, pivot)) in self.remainders.iter().zip(&self.echelon.pivots) {
+            if remainder < 0 || remainder % pivot != 0 {
+                return;
+            }
+            let value = (remainder / pivot) as u64;
+            self.values[variable] = value;
+            total += value;
+        }
+        if self.best.as_ref().is_none_or(|(best, _)| total < *best) {
+            self.best = Some((total, self.values.clone()));
+        }
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The smallest sum of all solutions with values up to `limit`.
+    fn brute_force(matrix: &[Vec<u64>], targets: &[u64], limit: u64) -> Option<u64> {
+        let variables = matrix[0].len();
+        let mut values = vec![0; variables];
+        let mut best = None;
+        loop {
+            let solves = matrix.iter().zip(targets).all(|(row, &target)| {
+                row.iter().zip(&values).map(|(a, x)| a * x).sum::<u64>() == target
+            });
+            if solves {
+                let sum = values.iter().sum::<u64>();
+                best = Some(best.map_or(sum, |best: u64| best.min(sum)));
+            }
+            let Some(i) = values.iter().position(|&x| x < limit) else {
+                return best;
+            };
+            values[..i].iter_mut().for_each(|x| *x = 0);
+            values[i] += 1;
+        }
+    }
+
+    fn check(matrix: &[Vec<u64>], targets: &[u64]) -> Option<u64> {
+        let solution = minimize_sum(matrix, targets).unwrap()?;
+        for (row, &target) in matrix.iter().zip(targets) {
+            assert_eq!(
+                row.iter().zip(&solution).map(|(a, x)| a * x).sum::<u64>(),
+                target
+            );
+        }
+        Some(solution.iter().sum())
+    }
+
+    #[test]
+    fn solves_small_systems() {
+        // The first machine of the day 10 example.
+        let matrix = vec![
+            vec![0, 0, 0, 0, 1, 1],
+            vec![0, 1, 0, 0, 0, 1],
+            vec![0, 0, 1, 1, 1, 0],
+            vec![1, 1, 0, 1, 0, 0],
+        ];
+        assert_eq!(check(&matrix, &[3, 5, 4, 7]), Some(10));
+
+        // x + y = 1 and x + y = 2 contradict each other, and 2x = 3 has no
+        // integer solution.
+        assert_eq!(check(&[vec![1, 1], vec![1, 1]], &[1, 2]), None);
+        assert_eq!(check(&[vec![2]], &[3]), None);
+        // The unused variable stays zero.
+        assert_eq!(
+            minimize_sum(&[vec![1, 0, 2]], &[4]),
+            Ok(Some(vec![0, 0, 2]))
+        );
+        assert_eq!(minimize_sum(&[], &[]), Ok(Some(vec![])));
+    }
+
+    #[test]
+    fn large_numbers() {
+        let big = 1 << 60;
+        assert_eq!(check(&[vec![1, 1], vec![1, 2]], &[big, big + 5]), Some(big));
+
+        // Eliminating multiplies the coefficients, which leaves `i128`.
+        let matrix = [vec![u64::MAX, u64::MAX - 2], vec![u64::MAX - 4, 1]];
+        assert_eq!(minimize_sum(&matrix, &[1, 1]), Err(SolveError::Overflow));
+        // Values up to the targets could add up beyond `u64`.
+        let matrix = [vec![1, 0], vec![0, 1]];
+        assert_eq!(
+            minimize_sum(&matrix, &[u64::MAX, 1]),
+            Err(SolveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn large_targets_match_brute_force() {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut random = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % n
+        };
+        // `x + Σ a_i · y_i = target`, where `x` takes up values far beyond
+        // 2^53 and the sums of the solutions lie close together.
+        for _ in 0..200 {
+            let base = (1 << 57) + random(1 << 57);
+            let coefficients: Vec<u64> = (0..2 + random(2)).map(|_| base + random(8)).collect();
+            let target = (1 << 60) + random(1 << 60);
+
+            let mut best = None;
+            let mut values = vec![0; coefficients.len()];
+            loop {
+                let moved = coefficients
+                    .iter()
+                    .zip(&values)
+                    .map(|(a, y)| a * y)
+                    .sum::<u64>();
+                if let Some(x) = target.checked_sub(moved) {
+                    let sum = x + values.iter().sum::<u64>();
+                    best = Some(best.map_or(sum, |best: u64| best.min(sum)));
+                }
+                let Some(i) = values.iter().position(|&y| y < target / base) else {
+                    break;
+                };
+                values[..i].iter_mut().for_each(|y| *y = 0);
+                values[i] += 1;
+            }
+
+            let row: Vec<u64> = [1]
+                .into_iter()
+                .chain(coefficients.iter().copied())
+                .collect();
+            assert_eq!(
+                check(&[row], &[target]),
+                best,
+                "{:?} {}",
+                coefficients,
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut random = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % n
+        };
+        for _ in 0..200 {
+            let (equations, variables) = (1 + random(4) as usize, 1 + random(4) as usize);
+            let matrix: Vec<Vec<u64>> = (0..equations)
+                .map(|_| (0..variables).map(|_| random(3)).collect())
+                .collect();
+            // Mostly reachable targets, with some that may not be.
+            let presses: Vec<u64> = (0..variables).map(|_| random(4)).collect();
+            let targets: Vec<u64> = matrix
+                .iter()
+                .map(|row| {
+                    row.iter().zip(&presses).map(|(a, x)| a * x).sum::<u64>() + random(4) / 3
+                })
+                .collect();
+            let limit = targets.iter().max().copied().unwrap_or(0);
+            assert_eq!(
+                check(&matrix, &targets),
+                brute_force(&matrix, &targets, limit),
+                "{:?} {:?}",
+                matrix,
+                targets
+            );
+        }
+    }
+}
@@ -1,14 +1,20 @@
 use std::{
-    cell::RefCell,
     error::Error,
     fmt::Display,
     hash::{self, Hash, Hasher},
+    io::Read,
+    path::PathBuf,
+    sync::Mutex,
 };
 
+use crate::util::input_cache::InputCache;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PuzzleInputType {
     Example,
     Actual,
+    /// Input supplied on the command line, from a file or stdin.
+    Custom,
 }
 
 impl Display for PuzzleInputType {
@@ -16,6 +22,7 @@ impl Display for PuzzleInputType {
         match self {
             PuzzleInputType::Example => write!(f, "Example"),
             PuzzleInputType::Actual => write!(f, "Actual"),
+            PuzzleInputType::Custom => write!(f, "Custom"),
         }
     }
 }
@@ -26,34 +33,83 @@ pub trait PuzzleGetter {
     fn get_type(&self) -> PuzzleInputType;
 }
 
+pub static DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Resolves the Advent of Code host, preferring an explicit override over
+/// the `AOC_BASE_URL` environment variable.
+pub fn resolve_base_url(override_url: Option<&str>) -> String {
+    override_url
+        .map(str::to_owned)
+        .or_else(|| std::env::var("AOC_BASE_URL").ok())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_owned())
+        .trim_end_matches('/')
+        .to_owned()
+}
+
+/// Cookie-authenticated access to the Advent of Code site, shared by input
+/// fetching and answer submission.
+pub struct CookieSession {
+    base_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl CookieSession {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    pub fn cookie(&self) -> Result<String, Box<dyn Error>> {
+        Ok(std::env::var("AOC_COOKIE").map_err(|_| "AOC_COOKIE environment variable not set")?)
+    }
+
+    /// Hash of the session cookie, used to keep per-account files apart.
+    pub fn cookie_hash(&self) -> Result<u64, Box<dyn Error>> {
+        let mut hasher = hash::DefaultHasher::new();
+        self.cookie()?.hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    pub fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::blocking::RequestBuilder, Box<dyn Error>> {
+        let url = format!("{}{}", self.base_url, path);
+        Ok(self
+            .client
+            .request(method, &url)
+            .header("Cookie", format!("session={}", self.cookie()?)))
+    }
+}
+
 struct CookiePuzzleInputGetter {
-    cache: RefCell<Option<String>>,
+    session: CookieSession,
+    cache: Mutex<Option<String>>,
+    disk_cache: InputCache,
 }
 
 impl CookiePuzzleInputGetter {
     fn get_input(&self, year: u16, day: u8) -> Result<String, Box<dyn Error>> {
-        if let Some(cached) = &*self.cache.borrow() {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(cached) = &*cache {
             return Ok(cached.clone());
         }
-        let session_cookie =
-            std::env::var("AOC_COOKIE").expect("AOC_COOKIE environment variable not set");
-
-        let cache_path = format!("inputs/{}_{}_{}.txt", year, day, {
-            let mut hasher = hash::DefaultHasher::new();
-            session_cookie.hash(&mut hasher);
-            hasher.finish()
-        });
-        if let Ok(cached) = std::fs::read_to_string(&cache_path) {
-            let mut cache = self.cache.borrow_mut();
+
+        let session = self.session.cookie_hash()?;
+        if let Some(cached) = self.disk_cache.get(year, day, session) {
             cache.replace(cached.clone());
             return Ok(cached);
         }
 
-        let url = format!("https://adventofcode.com/{}/day/{}/input", year, day);
-        let client = reqwest::blocking::Client::new();
-        let response = client
-            .get(&url)
-            .header("Cookie", format!("session={}", session_cookie))
+        let response = self
+            .session
+            .request(
+                reqwest::Method::GET,
+                &format!("/{}/day/{}/input", year, day),
+            )?
             .send()?;
 
         if !response.status().is_success() {
@@ -65,15 +121,18 @@ impl CookiePuzzleInputGetter {
             .into());
         }
 
+        let status = response.status().as_u16();
         let input = response.text()?;
-        std::fs::create_dir_all("inputs")?;
-        std::fs::write(&cache_path, &input)?;
+        self.disk_cache.store(year, day, session, &input, status)?;
+        cache.replace(input.clone());
         Ok(input)
     }
 
-    fn new() -> Self {
+    fn new(base_url: String, disk_cache: InputCache) -> Self {
         Self {
-            cache: RefCell::new(None),
+            session: CookieSession::new(base_url),
+            cache: Mutex::new(None),
+            disk_cache,
         }
     }
 }
@@ -85,11 +144,11 @@ pub struct DayCookiePuzzleInputGetter {
 }
 
 impl DayCookiePuzzleInputGetter {
-    pub fn new(year: u16, day: u8) -> Self {
+    pub fn new(year: u16, day: u8, base_url: String, cache: InputCache) -> Self {
         Self {
             year,
             day,
-            inner: CookiePuzzleInputGetter::new(),
+            inner: CookiePuzzleInputGetter::new(base_url, cache),
         }
     }
 }
@@ -103,3 +162,51 @@ impl PuzzleGetter for DayCookiePuzzleInputGetter {
         PuzzleInputType::Actual
     }
 }
+
+/// Reads the input from a file, e.g. someone else's puzzle input.
+pub struct FilePuzzleGetter {
+    path: PathBuf,
+}
+
+impl FilePuzzleGetter {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl PuzzleGetter for FilePuzzleGetter {
+    fn get_input(&self) -> Result<String, Box<dyn Error>> {
+        std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read input {}: {}", self.path.display(), e).into())
+    }
+
+    fn get_type(&self) -> PuzzleInputType {
+        PuzzleInputType::Custom
+    }
+}
+
+/// Reads the input from stdin. Stdin can only be read once, so the input is
+/// kept for later runs.
+#[derive(Default)]
+pub struct StdinPuzzleGetter {
+    cache: Mutex<Option<String>>,
+}
+
+impl PuzzleGetter for StdinPuzzleGetter {
+    fn get_input(&self) -> Result<String, Box<dyn Error>> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(cached) = &*cache {
+            return Ok(cached.clone());
+        }
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Failed to read input from stdin: {}", e))?;
+        cache.replace(input.clone());
+        Ok(input)
+    }
+
+    fn get_type(&self) -> PuzzleInputType {
+        PuzzleInputType::Custom
+    }
+}
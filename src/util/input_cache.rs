@@ -0,0 +1,259 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Resolves the input cache directory, preferring an explicit override over
+/// the `AOC_CACHE_DIR` environment variable and the XDG cache directory.
+pub fn resolve_cache_dir(override_dir: Option<&str>) -> PathBuf {
+    if let Some(dir) = override_dir
+        .map(str::to_owned)
+        .or_else(|| std::env::var("AOC_CACHE_DIR").ok())
+    {
+        return PathBuf::from(dir);
+    }
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .map_or_else(|| PathBuf::from("inputs"), |dir| dir.join("aoc25"))
+}
+
+/// How a cached input was fetched, stored next to it as
+/// `{year}_{day}_{session}.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheMetadata {
+    /// Seconds since the Unix epoch.
+    pub fetched_at: u64,
+    pub status: u16,
+    pub bytes: u64,
+}
+
+impl CacheMetadata {
+    fn to_toml(&self) -> String {
+        format!(
+            "fetched_at = {}\nstatus = {}\nbytes = {}\n",
+            self.fetched_at, self.status, self.bytes
+        )
+    }
+
+    fn from_toml(content: &str) -> Option<Self> {
+        let table = content.parse::<toml::Table>().ok()?;
+        let field = |name| table.get(name)?.as_integer()?.try_into().ok();
+        Some(Self {
+            fetched_at: field("fetched_at")?,
+            status: field("status")?.try_into().ok()?,
+            bytes: field("bytes")?,
+        })
+    }
+}
+
+/// A cached puzzle input of one session.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub year: u16,
+    pub day: u8,
+    /// Hash of the session cookie the input belongs to.
+    pub session: String,
+    pub path: PathBuf,
+    /// Size of the input file.
+    pub bytes: u64,
+    /// Missing for entries cached before metadata was recorded.
+    pub metadata: Option<CacheMetadata>,
+}
+
+impl CacheEntry {
+    fn metadata_path(&self) -> PathBuf {
+        self.path.with_extension("toml")
+    }
+
+    /// Reasons the entry cannot be trusted, if any.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let Some(metadata) = &self.metadata else {
+            return vec!["missing or unreadable metadata".to_owned()];
+        };
+        if !(200..300).contains(&metadata.status) {
+            problems.push(format!("fetched with HTTP {}", metadata.status));
+        }
+        if metadata.bytes != self.bytes {
+            problems.push(format!(
+                "{} bytes on disk, {} fetched",
+                self.bytes, metadata.bytes
+            ));
+        }
+        if self.bytes == 0 {
+            problems.push("empty input".to_owned());
+        }
+        problems
+    }
+}
+
+impl Display for CacheEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} day {:2}  session {}  {:>6} bytes",
+            self.year, self.day, self.session, self.bytes
+        )?;
+        match &self.metadata {
+            Some(metadata) => write!(
+                f,
+                "  HTTP {}  fetched {} ago",
+                metadata.status,
+                format_age(now().saturating_sub(metadata.fetched_at))
+            ),
+            None => write!(f, "  no metadata"),
+        }
+    }
+}
+
+/// Puzzle inputs downloaded with a session cookie, stored as
+/// `{year}_{day}_{session}.txt` in a single directory.
+#[derive(Debug, Clone)]
+pub struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, year: u16, day: u8, session: u64) -> PathBuf {
+        self.dir.join(format!("{}_{}_{}.txt", year, day, session))
+    }
+
+    pub fn get(&self, year: u16, day: u8, session: u64) -> Option<String> {
+        std::fs::read_to_string(self.path(year, day, session)).ok()
+    }
+
+    pub fn store(
+        &self,
+        year: u16,
+        day: u8,
+        session: u64,
+        input: &str,
+        status: u16,
+    ) -> Result<(), Box<dyn Error>> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.path(year, day, session);
+        let metadata = CacheMetadata {
+            fetched_at: now(),
+            status,
+            bytes: input.len() as u64,
+        };
+        std::fs::write(path.with_extension("toml"), metadata.to_toml())?;
+        std::fs::write(&path, input)?;
+        Ok(())
+    }
+
+    /// All cached inputs, ordered by year, day and session.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>, Box<dyn Error>> {
+        let dir = match std::fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = Vec::new();
+        for file in dir {
+            let path = file?.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".txt"))
+            else {
+                continue;
+            };
+            let mut fields = name.splitn(3, '_');
+            let (Some(Ok(year)), Some(Ok(day)), Some(session)) = (
+                fields.next().map(str::parse),
+                fields.next().map(str::parse),
+                fields.next(),
+            ) else {
+                continue;
+            };
+            let mut entry = CacheEntry {
+                year,
+                day,
+                session: session.to_owned(),
+                bytes: std::fs::metadata(&path)?.len(),
+                path,
+                metadata: None,
+            };
+            entry.metadata = std::fs::read_to_string(entry.metadata_path())
+                .ok()
+                .and_then(|content| CacheMetadata::from_toml(&content));
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| (a.year, a.day, &a.session).cmp(&(b.year, b.day, &b.session)));
+        Ok(entries)
+    }
+
+    /// Removes the cached inputs of the year, or only of one of its days.
+    pub fn purge(&self, year: u16, day: Option<u8>) -> Result<Vec<CacheEntry>, Box<dyn Error>> {
+        let purged: Vec<CacheEntry> = self
+            .entries()?
+            .into_iter()
+            .filter(|e| e.year == year && day.is_none_or(|day| e.day == day))
+            .collect();
+        for entry in &purged {
+            std::fs::remove_file(&entry.path)?;
+            match std::fs::remove_file(entry.metadata_path()) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(purged)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn store_list_and_purge() {
+        let dir = std::env::temp_dir().join(format!("aoc25-cache-test-{}", std::process::id()));
+        let cache = InputCache::new(dir.clone());
+
+        cache.store(2025, 1, 42, "L68\n", 200).unwrap();
+        cache.store(2025, 2, 42, "", 200).unwrap();
+        std::fs::write(dir.join("2025_3_42.txt"), "1,2").unwrap();
+        assert_eq!(cache.get(2025, 1, 42).as_deref(), Some("L68\n"));
+
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.iter().map(|e| e.day).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(entries[0].metadata.as_ref().unwrap().bytes, 4);
+        assert!(entries[0].problems().is_empty());
+        assert_eq!(entries[1].problems(), ["empty input"]);
+        assert_eq!(entries[2].problems(), ["missing or unreadable metadata"]);
+
+        assert_eq!(cache.purge(2025, Some(2)).unwrap().len(), 1);
+        assert_eq!(cache.entries().unwrap().len(), 2);
+        assert_eq!(cache.purge(2025, None).unwrap().len(), 2);
+        assert!(std::fs::read_dir(&dir).unwrap().next().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
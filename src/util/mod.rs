@@ -1,3 +1,12 @@
+pub mod answers;
 pub mod benchmark;
+pub mod benchmark_history;
+pub mod dirs;
+pub mod dsu;
+pub mod graph;
+pub mod grid;
+pub mod ilp;
 pub mod input;
+pub mod input_cache;
 pub mod number;
+pub mod submit;
@@ -1,7 +1,375 @@
-pub fn parse_u8_slice_to_i64(slice: &[u8]) -> i64 {
-    slice
-        .iter()
-        .map(|&c| (c - b'0') as i64)
-        .reduce(|acc, next| acc * 10 + next)
-        .unwrap()
+use std::{error::Error, fmt::Display, marker::PhantomData};
+
+use crate::error::ParseError;
+
+/// Why a slice is not a number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberError {
+    Empty,
+    InvalidDigit(u8),
+    /// The number does not fit the integer type.
+    Overflow,
+}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberError::Empty => write!(f, "expected a number"),
+            NumberError::InvalidDigit(c) => write!(f, "invalid digit {:?}", *c as char),
+            NumberError::Overflow => write!(f, "number out of range"),
+        }
+    }
+}
+
+impl Error for NumberError {}
+
+/// A primitive integer type that numbers can be parsed into.
+pub trait Integer: Copy {
+    const SIGNED: bool;
+
+    /// The value with the given magnitude and sign, if it fits.
+    fn from_magnitude(magnitude: u64, is_negative: bool) -> Option<Self>;
+
+    /// Like [`Integer::from_magnitude`], for the rare numbers beyond `u64`.
+    fn from_wide_magnitude(magnitude: u128, is_negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = <$t>::MIN != 0;
+
+                #[inline]
+                fn from_magnitude(magnitude: u64, is_negative: bool) -> Option<Self> {
+                    if !is_negative {
+                        <$t>::try_from(magnitude).ok()
+                    } else if magnitude as u128 <= (<$t>::MIN as i128).unsigned_abs() {
+                        // Wrapping so that the minimum value, whose magnitude
+                        // does not fit positively, comes out right.
+                        Some((magnitude as $t).wrapping_neg())
+                    } else {
+                        None
+                    }
+                }
+
+                fn from_wide_magnitude(magnitude: u128, is_negative: bool) -> Option<Self> {
+                    if is_negative {
+                        <$t>::try_from(0i128.checked_sub_unsigned(magnitude)?).ok()
+                    } else {
+                        <$t>::try_from(magnitude).ok()
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+/// Parses an integer of any primitive type, ignoring surrounding whitespace.
+/// Signed types accept a leading `-`, all types a leading `+`.
+#[inline(always)]
+pub fn parse_int<T: Integer>(slice: &[u8]) -> Result<T, NumberError> {
+    // Plain digits are by far the most common, so they skip trimming and
+    // signs, falling back to the full parse only if they turn out not to be.
+    let magnitude = match slice.len() {
+        1..8 => parse_few_digits(slice),
+        8..=16 => parse_16_digits(slice),
+        _ => None,
+    };
+    match magnitude {
+        Some(magnitude) => T::from_magnitude(magnitude, false).ok_or(NumberError::Overflow),
+        None => parse_signed(slice),
+    }
+}
+
+/// Parses up to seven digits one at a time, which is cheaper than loading
+/// them into a word at these lengths. They are checked all at once: for
+/// digits, both `c - b'0'` and that plus 6 stay below 16.
+#[inline(always)]
+fn parse_few_digits(digits: &[u8]) -> Option<u64> {
+    let mut magnitude = 0;
+    let mut check = 0u32;
+    for &c in digits {
+        let digit = (c as u32).wrapping_sub(b'0' as u32);
+        check |= digit | digit.wrapping_add(6);
+        magnitude = magnitude * 10 + digit as u64;
+    }
+    (check < 16).then_some(magnitude)
+}
+
+/// Parses 8 to 16 digits as two overlapping words: the last eight digits,
+/// and the ones before them behind leading zeros.
+#[inline(always)]
+fn parse_16_digits(digits: &[u8]) -> Option<u64> {
+    let len = digits.len();
+    let first = u64::from_le_bytes(digits[..8].try_into().unwrap());
+    let last = u64::from_le_bytes(digits[len - 8..].try_into().unwrap());
+    // Shifting in two halves turns the shifts by 64 at either end into
+    // zeros instead of overflows, without branching on the length.
+    let (head, rest) = ((len - 8) * 4, (16 - len) * 4);
+    let high = first << rest << rest | 0x3030_3030_3030_3030 >> head >> head;
+    Some(parse_8_digits(high)? * 100_000_000 + parse_8_digits(last)?)
+}
+
+fn parse_signed<T: Integer>(slice: &[u8]) -> Result<T, NumberError> {
+    let slice = slice.trim_ascii();
+    let (is_negative, digits) = match slice.split_first() {
+        Some((b'-', digits)) if T::SIGNED => (true, digits),
+        Some((b'+', digits)) => (false, digits),
+        Some(_) => (false, slice),
+        None => return Err(NumberError::Empty),
+    };
+    // Any 19 digits fit a u64, so only longer numbers need overflow checks.
+    if digits.len() > 19 {
+        return T::from_wide_magnitude(parse_wide_magnitude(digits)?, is_negative)
+            .ok_or(NumberError::Overflow);
+    }
+    if digits.is_empty() {
+        return Err(NumberError::Empty);
+    }
+    T::from_magnitude(parse_magnitude(digits)?, is_negative).ok_or(NumberError::Overflow)
+}
+
+/// Parses `slice`, a subslice of `input`, reporting failures at its position
+/// in the input.
+pub fn parse_number<T: Integer>(input: &[u8], slice: &[u8]) -> Result<T, ParseError> {
+    parse_int(slice).map_err(|e| ParseError::at(input, slice, e.to_string()))
+}
+
+/// Parses up to 19 digits eight at a time, starting with the digits left
+/// over by the full chunks.
+#[inline(always)]
+fn parse_magnitude(digits: &[u8]) -> Result<u64, NumberError> {
+    let (head, chunks) = digits.split_at(digits.len() % 8);
+    let mut magnitude = 0;
+    if !head.is_empty() {
+        let Some(value) = parse_8_digits(load_short(head)) else {
+            return Err(invalid_digit(head));
+        };
+        magnitude = value;
+    }
+    for chunk in chunks.chunks_exact(8) {
+        let Some(value) = parse_8_digits(u64::from_le_bytes(chunk.try_into().unwrap())) else {
+            return Err(invalid_digit(chunk));
+        };
+        magnitude = magnitude * 100_000_000 + value;
+    }
+    Ok(magnitude)
+}
+
+/// Loads 1 to 7 digits into a little-endian word behind leading zeros, using
+/// two overlapping reads rather than a loop over the bytes.
+#[inline(always)]
+fn load_short(head: &[u8]) -> u64 {
+    let len = head.len();
+    let word = if len >= 4 {
+        let low = u32::from_le_bytes(head[..4].try_into().unwrap()) as u64;
+        let high = u32::from_le_bytes(head[len - 4..].try_into().unwrap()) as u64;
+        low | high << ((len - 4) * 8)
+    } else if len >= 2 {
+        let low = u16::from_le_bytes(head[..2].try_into().unwrap()) as u64;
+        let high = u16::from_le_bytes(head[len - 2..].try_into().unwrap()) as u64;
+        low | high << ((len - 2) * 8)
+    } else {
+        head[0] as u64
+    };
+    word << ((8 - len) * 8) | 0x3030_3030_3030_3030 >> (len * 8)
+}
+
+#[cold]
+fn invalid_digit(digits: &[u8]) -> NumberError {
+    let c = *digits.iter().find(|c| !c.is_ascii_digit()).unwrap();
+    NumberError::InvalidDigit(c)
+}
+
+fn parse_wide_magnitude(digits: &[u8]) -> Result<u128, NumberError> {
+    digits.iter().try_fold(0u128, |acc, &c| {
+        let digit = digit(c)?;
+        acc.checked_mul(10)
+            .and_then(|acc| acc.checked_add(digit as u128))
+            .ok_or(NumberError::Overflow)
+    })
+}
+
+#[inline]
+fn digit(c: u8) -> Result<u8, NumberError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        _ => Err(NumberError::InvalidDigit(c)),
+    }
+}
+
+/// Parses eight ASCII digits, loaded little-endian, at once, or returns
+/// `None` if any of them is not a digit.
+#[inline(always)]
+fn parse_8_digits(value: u64) -> Option<u64> {
+    // Digits are 0x30..=0x39, so neither subtracting 0x30 nor adding 0x46
+    // sets their high bits. The lowest other byte sets one of them, as no
+    // borrow or carry reaches it from the digits below.
+    let digits = value.wrapping_sub(0x3030_3030_3030_3030);
+    if (digits | value.wrapping_add(0x4646_4646_4646_4646)) & 0x8080_8080_8080_8080 != 0 {
+        return None;
+    }
+    // Combine neighbouring digits into pairs, then quadruples, then the whole.
+    let value = digits;
+    let value = (value * 10 + (value >> 8)) & 0x00FF_00FF_00FF_00FF;
+    let value = (value * 100 + (value >> 16)) & 0x0000_FFFF_0000_FFFF;
+    Some((value * 10000 + (value >> 32)) & 0xFFFF_FFFF)
+}
+
+/// The integers in a line, skipping whatever separates them; a `-` directly
+/// before digits makes them negative. Created by [`integers`].
+pub struct Integers<'a, T> {
+    rest: &'a [u8],
+    integer: PhantomData<T>,
+}
+
+/// Extracts the integers from `line`, e.g. `1`, `-2` and `3` from
+/// `p=1,-2 v=3`, each together with the slice it was parsed from.
+pub fn integers<T: Integer>(line: &[u8]) -> Integers<'_, T> {
+    Integers {
+        rest: line,
+        integer: PhantomData,
+    }
+}
+
+impl<T: Integer> Integers<'_, T> {
+    /// Reports failures as [`ParseError`]s at their position in `input`, the
+    /// whole input the line is part of.
+    pub fn within(self, input: &[u8]) -> impl Iterator<Item = Result<T, ParseError>> {
+        self.map(|(slice, value)| value.map_err(|e| ParseError::at(input, slice, e.to_string())))
+    }
+}
+
+impl<'a, T: Integer> Iterator for Integers<'a, T> {
+    type Item = (&'a [u8], Result<T, NumberError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first_digit = self.rest.iter().position(u8::is_ascii_digit)?;
+        let start = match first_digit.checked_sub(1) {
+            Some(sign) if T::SIGNED && self.rest[sign] == b'-' => sign,
+            _ => first_digit,
+        };
+        let end = self.rest[first_digit..]
+            .iter()
+            .position(|c| !c.is_ascii_digit())
+            .map_or(self.rest.len(), |len| first_digit + len);
+        let slice = &self.rest[start..end];
+        self.rest = &self.rest[end..];
+        Some((slice, parse_int(slice)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_or_explains() {
+        assert_eq!(parse_int::<i64>(b"1234"), Ok(1234));
+        assert_eq!(parse_int::<i64>(b""), Err(NumberError::Empty));
+        assert_eq!(
+            parse_int::<i64>(b"12a"),
+            Err(NumberError::InvalidDigit(b'a'))
+        );
+        assert_eq!(
+            parse_int::<i64>(b"99999999999999999999"),
+            Err(NumberError::Overflow)
+        );
+
+        let input = b"7,\n8,9x";
+        let error = parse_number::<i64>(input, &input[5..7]).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.message, "invalid digit 'x'");
+    }
+
+    #[test]
+    fn signs_widths_and_whitespace() {
+        assert_eq!(parse_int::<i8>(b"-128"), Ok(i8::MIN));
+        assert_eq!(parse_int::<i8>(b"128"), Err(NumberError::Overflow));
+        assert_eq!(parse_int::<u8>(b" +255\n"), Ok(255));
+        assert_eq!(parse_int::<u8>(b"-1"), Err(NumberError::InvalidDigit(b'-')));
+        assert_eq!(parse_int::<i32>(b"-"), Err(NumberError::Empty));
+        assert_eq!(
+            parse_int::<i128>(b"-170141183460469231731687303715884105728"),
+            Ok(i128::MIN)
+        );
+        assert_eq!(
+            parse_int::<u128>(b"340282366920938463463374607431768211455"),
+            Ok(u128::MAX)
+        );
+        assert_eq!(
+            parse_int::<u128>(b"340282366920938463463374607431768211456"),
+            Err(NumberError::Overflow)
+        );
+        assert_eq!(parse_int::<u64>(b"18446744073709551615"), Ok(u64::MAX));
+        assert_eq!(
+            parse_int::<u64>(b"1234567x90"),
+            Err(NumberError::InvalidDigit(b'x'))
+        );
+    }
+
+    #[test]
+    fn eight_digits_at_once() {
+        for value in [
+            0, 1, 9, 10, 12345678, 87654321, 99999999, 10000000, 40400404,
+        ] {
+            let chunk = format!("{:08}", value);
+            let chunk = u64::from_le_bytes(chunk.as_bytes().try_into().unwrap());
+            assert_eq!(parse_8_digits(chunk), Some(value));
+        }
+        for position in 0..8 {
+            for c in 0..=u8::MAX {
+                let mut chunk = *b"12345678";
+                chunk[position] = c;
+                let parsed = parse_8_digits(u64::from_le_bytes(chunk));
+                assert_eq!(parsed.is_some(), c.is_ascii_digit(), "{:?}", chunk);
+            }
+        }
+        for len in 1..8 {
+            let digits = &b"1234567"[..len];
+            let parsed = parse_8_digits(load_short(digits)).unwrap();
+            assert_eq!(parsed.to_string().as_bytes(), digits);
+        }
+    }
+
+    #[test]
+    fn every_length() {
+        for len in 1..=19 {
+            let digits = &b"9876543210987654321"[..len];
+            let expected = std::str::from_utf8(digits).unwrap().parse::<u64>();
+            assert_eq!(parse_int::<u64>(digits), Ok(expected.unwrap()));
+            for position in 0..len {
+                for c in [b'*', b'/', b':', b' ', 0, 0xB9] {
+                    let mut digits = digits.to_vec();
+                    digits[position] = c;
+                    let parsed = parse_int::<u64>(&digits);
+                    let expected = std::str::from_utf8(&digits)
+                        .ok()
+                        .and_then(|digits| digits.trim_ascii().parse::<u64>().ok());
+                    assert_eq!(parsed.ok(), expected, "{:?}", digits);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn integers_in_a_line() {
+        let values: Vec<i64> = integers(b"p=0,-4 v=3,-3 x-y 12")
+            .map(|(_, value)| value.unwrap())
+            .collect();
+        assert_eq!(values, [0, -4, 3, -3, 12]);
+
+        let values: Vec<usize> = integers(b"(1,3) {3,5,4,7}")
+            .map(|(_, value)| value.unwrap())
+            .collect();
+        assert_eq!(values, [1, 3, 3, 5, 4, 7]);
+
+        let (slice, value) = integers::<u32>(b"a-5").next().unwrap();
+        assert_eq!((slice, value), (&b"5"[..], Ok(5)));
+    }
 }
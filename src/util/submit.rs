@@ -0,0 +1,322 @@
+use std::{error::Error, fmt::Display, path::PathBuf};
+
+use crate::{
+    day::Part,
+    util::{dirs::data_dir, input::CookieSession},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmissionVerdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// The site asked us to wait before submitting again.
+    RateLimited {
+        wait_seconds: Option<u64>,
+    },
+    /// The part has already been solved on this account.
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl SubmissionVerdict {
+    fn to_log_token(&self) -> &'static str {
+        match self {
+            SubmissionVerdict::Correct => "correct",
+            SubmissionVerdict::TooHigh => "too_high",
+            SubmissionVerdict::TooLow => "too_low",
+            SubmissionVerdict::Wrong => "wrong",
+            SubmissionVerdict::RateLimited { .. } => "rate_limited",
+            SubmissionVerdict::AlreadySolved => "already_solved",
+            SubmissionVerdict::Unknown(_) => "unknown",
+        }
+    }
+
+    fn from_log_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "correct" => SubmissionVerdict::Correct,
+            "too_high" => SubmissionVerdict::TooHigh,
+            "too_low" => SubmissionVerdict::TooLow,
+            "wrong" => SubmissionVerdict::Wrong,
+            "rate_limited" => SubmissionVerdict::RateLimited { wait_seconds: None },
+            "already_solved" => SubmissionVerdict::AlreadySolved,
+            "unknown" => SubmissionVerdict::Unknown(String::new()),
+            _ => return None,
+        })
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            SubmissionVerdict::TooHigh | SubmissionVerdict::TooLow | SubmissionVerdict::Wrong
+        )
+    }
+}
+
+impl Display for SubmissionVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmissionVerdict::Correct => write!(f, "That's the right answer"),
+            SubmissionVerdict::TooHigh => write!(f, "Wrong answer: too high"),
+            SubmissionVerdict::TooLow => write!(f, "Wrong answer: too low"),
+            SubmissionVerdict::Wrong => write!(f, "Wrong answer"),
+            SubmissionVerdict::RateLimited {
+                wait_seconds: Some(seconds),
+            } => write!(f, "Rate limited, wait {}m {}s", seconds / 60, seconds % 60),
+            SubmissionVerdict::RateLimited { wait_seconds: None } => write!(f, "Rate limited"),
+            SubmissionVerdict::AlreadySolved => write!(f, "Already solved"),
+            SubmissionVerdict::Unknown(message) => write!(f, "Unrecognized response: {}", message),
+        }
+    }
+}
+
+/// Interprets the HTML the site returns after posting an answer.
+pub fn parse_submission_response(html: &str) -> SubmissionVerdict {
+    let article = regex::Regex::new(r"(?s)<article[^>]*>(.*?)</article>")
+        .unwrap()
+        .captures(html)
+        .map(|c| c.get(1).unwrap().as_str())
+        .unwrap_or(html);
+    let text = regex::Regex::new(r"<[^>]*>")
+        .unwrap()
+        .replace_all(article, "");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.contains("That's the right answer") {
+        SubmissionVerdict::Correct
+    } else if text.contains("You gave an answer too recently") {
+        let wait_seconds = regex::Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait")
+            .unwrap()
+            .captures(&text)
+            .map(|c| {
+                let minutes = c.get(1).map_or(0, |m| m.as_str().parse::<u64>().unwrap());
+                let seconds = c.get(2).unwrap().as_str().parse::<u64>().unwrap();
+                minutes * 60 + seconds
+            });
+        SubmissionVerdict::RateLimited { wait_seconds }
+    } else if text.contains("your answer is too high") {
+        SubmissionVerdict::TooHigh
+    } else if text.contains("your answer is too low") {
+        SubmissionVerdict::TooLow
+    } else if text.contains("That's not the right answer") {
+        SubmissionVerdict::Wrong
+    } else if text.contains("You don't seem to be solving the right level") {
+        SubmissionVerdict::AlreadySolved
+    } else {
+        SubmissionVerdict::Unknown(text)
+    }
+}
+
+struct SubmissionRecord {
+    part: u8,
+    verdict: SubmissionVerdict,
+    answer: String,
+}
+
+/// Local log of past submissions, stored in `answers/{year}_{day}_submissions.log`
+/// under the [data directory](data_dir).
+///
+/// Each line holds the unix timestamp, part, verdict and submitted answer,
+/// separated by tabs.
+pub struct SubmissionLog {
+    path: PathBuf,
+    records: Vec<SubmissionRecord>,
+}
+
+impl SubmissionLog {
+    pub fn load(year: u16, day: u8) -> Result<Self, Box<dyn Error>> {
+        let path = data_dir()
+            .join("answers")
+            .join(format!("{}_{}_submissions.log", year, day));
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let records = content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let mut fields = line.splitn(4, '\t').skip(1);
+                let part = fields.next()?.parse::<u8>().ok()?;
+                let verdict = SubmissionVerdict::from_log_token(fields.next()?)?;
+                let answer = fields.next()?.to_owned();
+                Some(SubmissionRecord {
+                    part,
+                    verdict,
+                    answer,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| format!("Malformed submission log {}", path.display()))?;
+        Ok(Self { path, records })
+    }
+
+    /// Returns why `answer` should not be submitted, based on earlier verdicts.
+    pub fn rejection_reason(&self, part: Part, answer: &str) -> Option<String> {
+        let numeric = answer.parse::<i128>().ok();
+        for record in self.records.iter().filter(|r| r.part == part.to_number()) {
+            if record.verdict.is_wrong() && record.answer == answer {
+                return Some(format!("{} was already submitted and is wrong", answer));
+            }
+            let (Some(value), Ok(bound)) = (numeric, record.answer.parse::<i128>()) else {
+                continue;
+            };
+            match record.verdict {
+                SubmissionVerdict::TooHigh if value >= bound => {
+                    return Some(format!("{} is not below known too-high {}", answer, bound));
+                }
+                SubmissionVerdict::TooLow if value <= bound => {
+                    return Some(format!("{} is not above known too-low {}", answer, bound));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn append(
+        &mut self,
+        part: Part,
+        answer: &str,
+        verdict: SubmissionVerdict,
+    ) -> Result<(), Box<dyn Error>> {
+        use std::io::Write;
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}",
+            timestamp,
+            part,
+            verdict.to_log_token(),
+            answer
+        )?;
+        self.records.push(SubmissionRecord {
+            part: part.to_number(),
+            verdict,
+            answer: answer.to_owned(),
+        });
+        Ok(())
+    }
+}
+
+pub struct DayCookieAnswerSubmitter {
+    year: u16,
+    day: u8,
+    session: CookieSession,
+}
+
+impl DayCookieAnswerSubmitter {
+    pub fn new(year: u16, day: u8, base_url: String) -> Self {
+        Self {
+            year,
+            day,
+            session: CookieSession::new(base_url),
+        }
+    }
+
+    pub fn submit(&self, part: Part, answer: &str) -> Result<SubmissionVerdict, Box<dyn Error>> {
+        let response = self
+            .session
+            .request(
+                reqwest::Method::POST,
+                &format!("/{}/day/{}/answer", self.year, self.day),
+            )?
+            .form(&[("level", part.to_string().as_str()), ("answer", answer)])
+            .send()?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to submit answer for day {}: HTTP {}",
+                self.day,
+                response.status()
+            )
+            .into());
+        }
+
+        Ok(parse_submission_response(&response.text()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_responses() {
+        let wrap = |text: &str| format!("<main><article><p>{}</p></article></main>", text);
+        assert_eq!(
+            parse_submission_response(&wrap(
+                "That's the right answer! You are <em>one gold star</em> closer."
+            )),
+            SubmissionVerdict::Correct
+        );
+        assert_eq!(
+            parse_submission_response(&wrap(
+                "That's not the right answer; your answer is too high. Please wait one minute before trying again."
+            )),
+            SubmissionVerdict::TooHigh
+        );
+        assert_eq!(
+            parse_submission_response(&wrap(
+                "That's not the right answer; your answer is too low."
+            )),
+            SubmissionVerdict::TooLow
+        );
+        assert_eq!(
+            parse_submission_response(&wrap(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait."
+            )),
+            SubmissionVerdict::RateLimited {
+                wait_seconds: Some(65)
+            }
+        );
+        assert_eq!(
+            parse_submission_response(&wrap(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            SubmissionVerdict::AlreadySolved
+        );
+    }
+
+    #[test]
+    fn reject_known_wrong_and_out_of_bounds() {
+        let log = SubmissionLog {
+            path: PathBuf::new(),
+            records: vec![
+                SubmissionRecord {
+                    part: 1,
+                    verdict: SubmissionVerdict::TooHigh,
+                    answer: "500".to_owned(),
+                },
+                SubmissionRecord {
+                    part: 1,
+                    verdict: SubmissionVerdict::TooLow,
+                    answer: "100".to_owned(),
+                },
+                SubmissionRecord {
+                    part: 1,
+                    verdict: SubmissionVerdict::Wrong,
+                    answer: "250".to_owned(),
+                },
+            ],
+        };
+        assert!(log.rejection_reason(Part::One, "500").is_some());
+        assert!(log.rejection_reason(Part::One, "600").is_some());
+        assert!(log.rejection_reason(Part::One, "100").is_some());
+        assert!(log.rejection_reason(Part::One, "250").is_some());
+        assert!(log.rejection_reason(Part::One, "300").is_none());
+        assert!(log.rejection_reason(Part::Two, "600").is_none());
+    }
+}
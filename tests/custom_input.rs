@@ -0,0 +1,59 @@
+mod common;
+
+use common::{TestDir, run_aoc, run_aoc_with_stdin, stdout};
+
+static DAY_1_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+#[test]
+fn input_from_file() {
+    let dir = TestDir::new("custom-file");
+    std::fs::write(dir.path().join("day1.txt"), DAY_1_INPUT).unwrap();
+
+    let output = run_aoc(&dir, &["1", "--input", "day1.txt"], &[]);
+
+    let text = stdout(&output);
+    assert!(text.contains("[ 1.1c] 3"), "{}", text);
+    assert!(text.contains("[ 1.2c] 6"), "{}", text);
+    assert!(!text.contains("1.1e") && !text.contains("1.1a"), "{}", text);
+    assert!(output.status.success());
+}
+
+#[test]
+fn input_from_stdin_is_shared_by_parts() {
+    let dir = TestDir::new("custom-stdin");
+
+    let output = run_aoc_with_stdin(&dir, &["1", "--input", "-"], DAY_1_INPUT);
+
+    let text = stdout(&output);
+    assert!(text.contains("[ 1.1c] 3"), "{}", text);
+    assert!(text.contains("[ 1.2c] 6"), "{}", text);
+}
+
+#[test]
+fn missing_file_is_reported() {
+    let dir = TestDir::new("custom-missing");
+
+    let output = run_aoc(&dir, &["1.1", "--input=missing.txt"], &[]);
+
+    let text = stdout(&output);
+    assert!(
+        text.contains("Failed to read input missing.txt"),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn malformed_input_is_pointed_out() {
+    let dir = TestDir::new("custom-malformed");
+    std::fs::write(dir.path().join("day1.txt"), "L68\nR4x\n").unwrap();
+
+    let output = run_aoc(&dir, &["1.1", "--input", "day1.txt"], &[]);
+
+    let text = stdout(&output);
+    assert!(
+        text.contains("Day 1 input, line 2, column 2: invalid digit 'x': \"4x\""),
+        "{}",
+        text
+    );
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::{MockServer, TestDir, run_aoc, stdout};
+
+static DAY_01_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+#[test]
+fn fetches_input_with_session_cookie() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-cookie");
+
+    let output = run_aoc(
+        &dir,
+        &["1.1a"],
+        &[("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())],
+    );
+
+    assert!(stdout(&output).contains("[ 1.1a]"));
+    assert!(stdout(&output).contains(" 3 "), "{}", stdout(&output));
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/2025/day/1/input");
+    assert_eq!(requests[0].header("cookie"), Some("session=secret"));
+}
+
+#[test]
+fn base_url_flag_overrides_environment() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-flag");
+    let base_url_flag = format!("--base-url={}", server.url());
+
+    let output = run_aoc(
+        &dir,
+        &["1.1a", &base_url_flag],
+        &[
+            ("AOC_COOKIE", "secret"),
+            ("AOC_BASE_URL", "http://127.0.0.1:1"),
+        ],
+    );
+
+    assert!(stdout(&output).contains(" 3 "), "{}", stdout(&output));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn caches_input_between_runs() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-cache");
+    let envs = [("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())];
+
+    run_aoc(&dir, &["1.1a"], &envs);
+    let output = run_aoc(&dir, &["1a"], &envs);
+
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(dir.files_in("inputs").len(), 2);
+    assert!(dir.files_in("inputs")[0].starts_with("2025_1_"));
+    assert!(dir.files_in("inputs")[0].ends_with(".toml"));
+    assert!(dir.files_in("inputs")[1].ends_with(".txt"));
+    assert!(stdout(&output).contains("[ 1.2a]"));
+    assert!(stdout(&output).contains(" 6 "), "{}", stdout(&output));
+}
+
+#[test]
+fn separate_cache_per_session() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-session");
+
+    run_aoc(
+        &dir,
+        &["1.1a"],
+        &[("AOC_COOKIE", "first"), ("AOC_BASE_URL", server.url())],
+    );
+    run_aoc(
+        &dir,
+        &["1.1a"],
+        &[("AOC_COOKIE", "second"), ("AOC_BASE_URL", server.url())],
+    );
+
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(dir.files_in("inputs").len(), 4);
+}
+
+#[test]
+fn reports_http_errors_without_caching() {
+    let server = MockServer::start(|_| (404, "Not Found".to_owned()));
+    let dir = TestDir::new("fetch-404");
+
+    let output = run_aoc(
+        &dir,
+        &["1.1a"],
+        &[("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())],
+    );
+
+    assert!(stdout(&output).contains("HTTP 404"), "{}", stdout(&output));
+    assert!(dir.files_in("inputs").is_empty());
+}
+
+#[test]
+fn reports_missing_cookie() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-no-cookie");
+
+    let output = run_aoc(&dir, &["1.1a"], &[("AOC_BASE_URL", server.url())]);
+
+    assert!(
+        stdout(&output).contains("AOC_COOKIE"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn cache_dir_flag_overrides_environment() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-cache-dir");
+
+    run_aoc(
+        &dir,
+        &["1.1a", "--cache-dir", "elsewhere"],
+        &[("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())],
+    );
+
+    assert!(dir.files_in("inputs").is_empty());
+    assert_eq!(dir.files_in("elsewhere").len(), 2);
+}
+
+#[test]
+fn cache_list_verify_and_purge() {
+    let server = MockServer::start(|_| (200, DAY_01_INPUT.to_owned()));
+    let dir = TestDir::new("fetch-cache-commands");
+    let envs = [("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())];
+    run_aoc(&dir, &["1.1a"], &envs);
+
+    let list = stdout(&run_aoc(&dir, &["cache", "list"], &envs));
+    assert!(list.contains("1 cached input(s)"), "{}", list);
+    assert!(list.contains("2025 day  1"), "{}", list);
+    assert!(list.contains("HTTP 200"), "{}", list);
+    assert!(
+        list.contains(&format!("{} bytes", DAY_01_INPUT.len())),
+        "{}",
+        list
+    );
+
+    let verify = run_aoc(&dir, &["cache", "verify"], &envs);
+    assert!(verify.status.success(), "{}", stdout(&verify));
+
+    let input = dir.path().join("inputs").join(&dir.files_in("inputs")[1]);
+    std::fs::write(input, "L68\n").unwrap();
+    let verify = run_aoc(&dir, &["cache", "verify"], &envs);
+    assert!(!verify.status.success());
+    assert!(
+        stdout(&verify).contains("bytes on disk"),
+        "{}",
+        stdout(&verify)
+    );
+
+    let purge = stdout(&run_aoc(&dir, &["cache", "purge", "1"], &envs));
+    assert!(purge.contains("Purged 1 cached input(s)"), "{}", purge);
+    assert!(dir.files_in("inputs").is_empty());
+}
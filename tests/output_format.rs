@@ -0,0 +1,77 @@
+mod common;
+
+use common::{TestDir, run_aoc};
+
+#[test]
+fn json_reports_one_record_per_run() {
+    let dir = TestDir::new("format-json");
+
+    let output = run_aoc(&dir, &["1e", "11.1e", "--format", "json"], &[]);
+
+    let json = String::from_utf8_lossy(&output.stdout);
+    assert!(json.trim_start().starts_with('['), "{}", json);
+    assert!(json.trim_end().ends_with(']'), "{}", json);
+    assert_eq!(json.matches("\"day\": ").count(), 3, "{}", json);
+    assert!(
+        json.contains("\"day\": 1, \"part\": 1, \"input\": \"example\", \"example\": \"example\", \"status\": \"ok\", \"error\": null, \"answer\": \"3\", \"verdict\": \"correct\""),
+        "{}",
+        json
+    );
+    assert!(json.contains("\"example\": \"you_to_out\""), "{}", json);
+    assert!(json.contains("\"solve\": {\"n\": 1, "), "{}", json);
+    assert!(output.status.success());
+}
+
+#[test]
+fn csv_reports_header_and_rows() {
+    let dir = TestDir::new("format-csv");
+
+    let output = run_aoc(&dir, &["1e", "--format=csv"], &[]);
+
+    let csv = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", csv);
+    assert!(lines[0].starts_with("day,part,input,example,status,error,answer,verdict"));
+    assert!(
+        lines[1].starts_with("1,1,example,example,ok,,3,correct,"),
+        "{}",
+        csv
+    );
+    assert!(
+        lines[2].starts_with("1,2,example,example,ok,,6,correct,"),
+        "{}",
+        csv
+    );
+}
+
+#[test]
+fn errors_are_reported_as_records() {
+    let dir = TestDir::new("format-error");
+
+    let output = run_aoc(&dir, &["1.1a", "--format=json"], &[]);
+
+    let json = String::from_utf8_lossy(&output.stdout);
+    assert!(json.contains("\"status\": \"error\""), "{}", json);
+    assert!(json.contains("AOC_COOKIE"), "{}", json);
+}
+
+#[test]
+fn no_colors_when_not_a_terminal() {
+    let dir = TestDir::new("format-plain");
+
+    let output = run_aoc(&dir, &["1e"], &[]);
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(text.contains("[ 1.1e] 3 ✓"), "{}", text);
+    assert!(!text.contains('\x1b'), "{}", text);
+}
+
+#[test]
+fn unknown_format_is_rejected() {
+    let dir = TestDir::new("format-unknown");
+
+    let output = run_aoc(&dir, &["1e", "--format=xml"], &[]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Unknown format"));
+}
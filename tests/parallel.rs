@@ -0,0 +1,33 @@
+mod common;
+
+use common::{TestDir, run_aoc};
+
+#[test]
+fn jobs_keep_results_in_order() {
+    let dir = TestDir::new("parallel-order");
+
+    let output = run_aoc(&dir, &["1-6e", "--jobs", "4"], &[]);
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let labels: Vec<&str> = text
+        .lines()
+        .filter(|line| line.starts_with('['))
+        .map(|line| &line[..7])
+        .collect();
+    let expected: Vec<String> = (1..=6)
+        .flat_map(|day| (1..=2).map(move |part| format!("[{:2}.{}e]", day, part)))
+        .collect();
+    assert_eq!(labels, expected, "{}", text);
+    assert!(text.contains("Ran 12 target(s) on 4 jobs"), "{}", text);
+    assert!(output.status.success());
+}
+
+#[test]
+fn jobs_rejected_when_benchmarking() {
+    let dir = TestDir::new("parallel-benchmark");
+
+    let output = run_aoc(&dir, &["1e", "-j", "2", "-b"], &[]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("--benchmark"));
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::{MockServer, Request, TestDir, run_aoc, stdout};
+
+static DAY_01_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+fn site(answer_html: &'static str) -> impl Fn(&Request) -> (u16, String) {
+    move |request| match request.method.as_str() {
+        "GET" => (200, DAY_01_INPUT.to_owned()),
+        _ => (
+            200,
+            format!(
+                "<html><main><article><p>{}</p></article></main></html>",
+                answer_html
+            ),
+        ),
+    }
+}
+
+fn posts(server: &MockServer) -> Vec<Request> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "POST")
+        .collect()
+}
+
+#[test]
+fn correct_answer_is_recorded() {
+    let server = MockServer::start(site(
+        "That's the right answer! You are <em>one gold star</em> closer.",
+    ));
+    let dir = TestDir::new("submit-correct");
+    let envs = [("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())];
+
+    let output = run_aoc(&dir, &["submit", "1.1"], &envs);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    let posts = posts(&server);
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].path, "/2025/day/1/answer");
+    assert_eq!(posts[0].body, "level=1&answer=3");
+    assert_eq!(posts[0].header("cookie"), Some("session=secret"));
+    let answers = std::fs::read_to_string(dir.path().join("answers/2025_1.toml")).unwrap();
+    assert!(answers.contains("actual = 3"), "{}", answers);
+
+    let output = run_aoc(&dir, &["submit", "1.1"], &envs);
+    assert!(!output.status.success());
+    assert!(
+        stdout(&output).contains("already solved"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        server
+            .requests()
+            .iter()
+            .filter(|r| r.method == "POST")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn known_wrong_answer_is_not_resubmitted() {
+    let server = MockServer::start(site(
+        "That's not the right answer; your answer is too high. Please wait one minute before trying again.",
+    ));
+    let dir = TestDir::new("submit-wrong");
+    let envs = [("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())];
+
+    let output = run_aoc(&dir, &["submit", "1.1"], &envs);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("too high"), "{}", stdout(&output));
+    let log = std::fs::read_to_string(dir.path().join("answers/2025_1_submissions.log")).unwrap();
+    assert!(log.trim_end().ends_with("\t1\ttoo_high\t3"), "{}", log);
+
+    let output = run_aoc(&dir, &["submit", "1.1"], &envs);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("Refusing"), "{}", stdout(&output));
+    assert_eq!(posts(&server).len(), 1);
+}
+
+#[test]
+fn answer_outside_recorded_bounds_is_not_submitted() {
+    let server = MockServer::start(site("That's the right answer!"));
+    let dir = TestDir::new("submit-bounds");
+    std::fs::create_dir_all(dir.path().join("answers")).unwrap();
+    std::fs::write(
+        dir.path().join("answers/2025_1_submissions.log"),
+        "1700000000\t1\ttoo_low\t5\n",
+    )
+    .unwrap();
+
+    let output = run_aoc(
+        &dir,
+        &["submit", "1.1"],
+        &[("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())],
+    );
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("too-low 5"), "{}", stdout(&output));
+    assert!(posts(&server).is_empty());
+}
+
+#[test]
+fn rate_limit_is_reported() {
+    let server = MockServer::start(site(
+        "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 42s left to wait.",
+    ));
+    let dir = TestDir::new("submit-rate-limit");
+
+    let output = run_aoc(
+        &dir,
+        &["submit", "1.2"],
+        &[("AOC_COOKIE", "secret"), ("AOC_BASE_URL", server.url())],
+    );
+
+    assert!(!output.status.success());
+    assert!(
+        stdout(&output).contains("wait 0m 42s"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(posts(&server)[0].body, "level=2&answer=6");
+}